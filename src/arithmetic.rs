@@ -0,0 +1,382 @@
+/// Checked, saturating, wrapping and overflowing arithmetic for a size type wrapping `$inner`.
+///
+/// Addition and subtraction come with variants taking a plain byte count, matching the
+/// `Add<impl Into<u64>>` style operator impls; multiplication and division take scalars.
+macro_rules! arithmetic {
+    ($size:ident($inner:ty)) => {
+        impl $size {
+            arithmetic!(@ops $size, "Adds", rhs: $size => rhs.0, "the largest size",
+                checked_add = checked_add,
+                saturating_add = saturating_add,
+                wrapping_add = wrapping_add,
+                overflowing_add = overflowing_add);
+            arithmetic!(@ops $size, "Adds", bytes: $inner => bytes, "the largest size",
+                checked_add_bytes = checked_add,
+                saturating_add_bytes = saturating_add,
+                wrapping_add_bytes = wrapping_add,
+                overflowing_add_bytes = overflowing_add);
+            arithmetic!(@ops $size, "Subtracts", rhs: $size => rhs.0, "zero",
+                checked_sub = checked_sub,
+                saturating_sub = saturating_sub,
+                wrapping_sub = wrapping_sub,
+                overflowing_sub = overflowing_sub);
+            arithmetic!(@ops $size, "Subtracts", bytes: $inner => bytes, "zero",
+                checked_sub_bytes = checked_sub,
+                saturating_sub_bytes = saturating_sub,
+                wrapping_sub_bytes = wrapping_sub,
+                overflowing_sub_bytes = overflowing_sub);
+            arithmetic!(@ops $size, "Multiplies by", rhs: $inner => rhs, "the largest size",
+                checked_mul = checked_mul,
+                saturating_mul = saturating_mul,
+                wrapping_mul = wrapping_mul,
+                overflowing_mul = overflowing_mul);
+
+            /// Absolute difference between two sizes, which never overflows.
+            #[inline(always)]
+            pub const fn abs_diff(self, other: $size) -> $size {
+                $size(self.0.abs_diff(other.0))
+            }
+
+            /// Divides by `rhs`, returning `None` if it is zero.
+            #[inline(always)]
+            pub const fn checked_div(self, rhs: $inner) -> Option<$size> {
+                match self.0.checked_div(rhs) {
+                    Some(bytes) => Some($size(bytes)),
+                    None => None,
+                }
+            }
+
+            /// How many whole times `rhs` fits into the size, returning `None` if it is zero.
+            #[inline(always)]
+            pub const fn checked_div_size(self, rhs: $size) -> Option<$inner> {
+                self.0.checked_div(rhs.0)
+            }
+
+            /// Remainder after taking out as many whole `rhs` as fit, returning `None` if it
+            /// is zero.
+            #[inline(always)]
+            pub const fn checked_rem(self, rhs: $size) -> Option<$size> {
+                match self.0.checked_rem(rhs.0) {
+                    Some(bytes) => Some($size(bytes)),
+                    None => None,
+                }
+            }
+
+            /// How many chunks of size `rhs` are needed to hold the size, i.e. the quotient
+            /// rounded up.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `rhs` is zero.
+            #[inline(always)]
+            pub const fn div_ceil(self, rhs: $size) -> $inner {
+                let quotient = self.0 / rhs.0;
+                if self.0 % rhs.0 == 0 {
+                    quotient
+                } else {
+                    quotient + 1
+                }
+            }
+
+            /// Like [`div_ceil()`](Self::div_ceil), but returns `None` if `rhs` is zero.
+            #[inline(always)]
+            pub const fn checked_div_ceil(self, rhs: $size) -> Option<$inner> {
+                if rhs.0 == 0 {
+                    None
+                } else {
+                    Some(self.div_ceil(rhs))
+                }
+            }
+
+            /// The size as a fraction of `other`, e.g. `0.25` for a quarter. Infinite or NaN
+            /// if `other` is zero.
+            #[inline(always)]
+            pub fn ratio(&self, other: $size) -> f64 {
+                self.0 as f64 / other.0 as f64
+            }
+
+            /// Like [`ratio()`](Self::ratio), but returns `None` if `other` is zero.
+            #[inline(always)]
+            pub fn checked_ratio(&self, other: $size) -> Option<f64> {
+                if other.0 == 0 {
+                    None
+                } else {
+                    Some(self.ratio(other))
+                }
+            }
+        }
+    };
+
+    (@ops $size:ident, $verb:literal, $rhs:ident: $rhs_ty:ty => $value:expr, $bound:literal,
+        $checked:ident = $inner_checked:ident,
+        $saturating:ident = $inner_saturating:ident,
+        $wrapping:ident = $inner_wrapping:ident,
+        $overflowing:ident = $inner_overflowing:ident) => {
+        #[doc = concat!($verb, " `", stringify!($rhs), "`, returning `None` on overflow.")]
+        #[inline(always)]
+        pub const fn $checked(self, $rhs: $rhs_ty) -> Option<$size> {
+            match self.0.$inner_checked($value) {
+                Some(bytes) => Some($size(bytes)),
+                None => None,
+            }
+        }
+
+        #[doc = concat!($verb, " `", stringify!($rhs), "`, saturating at ", $bound, ".")]
+        #[inline(always)]
+        pub const fn $saturating(self, $rhs: $rhs_ty) -> $size {
+            $size(self.0.$inner_saturating($value))
+        }
+
+        #[doc = concat!($verb, " `", stringify!($rhs), "`, wrapping around at ", $bound, ".")]
+        #[inline(always)]
+        pub const fn $wrapping(self, $rhs: $rhs_ty) -> $size {
+            $size(self.0.$inner_wrapping($value))
+        }
+
+        #[doc = concat!(
+            $verb, " `", stringify!($rhs), "`, returning the wrapped result and whether it overflowed."
+        )]
+        #[inline(always)]
+        pub const fn $overflowing(self, $rhs: $rhs_ty) -> ($size, bool) {
+            let (bytes, overflowed) = self.0.$inner_overflowing($value);
+            ($size(bytes), overflowed)
+        }
+    };
+}
+
+/// Formats the byte count of a size type as a plain number in another radix, e.g. `{:#x}` for
+/// `0x1000`.
+macro_rules! radix_fmt {
+    ($size:ident) => {
+        radix_fmt!(@impl $size, LowerHex, UpperHex, Octal, Binary);
+    };
+    (@impl $size:ident, $($trait:ident),*) => {
+        $(
+            impl fmt::$trait for $size {
+                fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+                    fmt::$trait::fmt(&self.0, f)
+                }
+            }
+        )*
+    };
+}
+
+/// Operator impls for a size type wrapping `$inner`: `+`, `-`, `*` and `/` with another size
+/// or a byte count, `%` with another size, and the commutative `+` and `*` of each `$scalar`.
+macro_rules! operators {
+    ($size:ident($inner:ty), $($scalar:ty),*) => {
+        $(
+            impl Add<$size> for $scalar {
+                type Output = $size;
+                #[inline(always)]
+                fn add(self, rhs: $size) -> $size {
+                    $size(rhs.0 + (self as $inner))
+                }
+            }
+
+            impl Mul<$size> for $scalar {
+                type Output = $size;
+                #[inline(always)]
+                fn mul(self, rhs: $size) -> $size {
+                    $size(rhs.0 * (self as $inner))
+                }
+            }
+        )*
+
+        impl Add<$size> for $size {
+            type Output = $size;
+
+            #[inline(always)]
+            fn add(self, rhs: $size) -> $size {
+                $size(self.0 + rhs.0)
+            }
+        }
+
+        impl AddAssign<$size> for $size {
+            #[inline(always)]
+            fn add_assign(&mut self, rhs: $size) {
+                self.0 += rhs.0
+            }
+        }
+
+        impl<T> Add<T> for $size
+        where
+            T: Into<$inner>,
+        {
+            type Output = $size;
+            #[inline(always)]
+            fn add(self, rhs: T) -> $size {
+                $size(self.0 + (rhs.into()))
+            }
+        }
+
+        impl<T> AddAssign<T> for $size
+        where
+            T: Into<$inner>,
+        {
+            #[inline(always)]
+            fn add_assign(&mut self, rhs: T) {
+                self.0 += rhs.into();
+            }
+        }
+
+        impl Sub<$size> for $size {
+            type Output = $size;
+
+            #[inline(always)]
+            fn sub(self, rhs: $size) -> $size {
+                $size(self.0 - rhs.0)
+            }
+        }
+
+        impl SubAssign<$size> for $size {
+            #[inline(always)]
+            fn sub_assign(&mut self, rhs: $size) {
+                self.0 -= rhs.0
+            }
+        }
+
+        impl<T> Sub<T> for $size
+        where
+            T: Into<$inner>,
+        {
+            type Output = $size;
+            #[inline(always)]
+            fn sub(self, rhs: T) -> $size {
+                $size(self.0 - (rhs.into()))
+            }
+        }
+
+        impl<T> SubAssign<T> for $size
+        where
+            T: Into<$inner>,
+        {
+            #[inline(always)]
+            fn sub_assign(&mut self, rhs: T) {
+                self.0 -= rhs.into();
+            }
+        }
+
+        impl<T> Mul<T> for $size
+        where
+            T: Into<$inner>,
+        {
+            type Output = $size;
+            #[inline(always)]
+            fn mul(self, rhs: T) -> $size {
+                $size(self.0 * rhs.into())
+            }
+        }
+
+        impl<T> MulAssign<T> for $size
+        where
+            T: Into<$inner>,
+        {
+            #[inline(always)]
+            fn mul_assign(&mut self, rhs: T) {
+                self.0 *= rhs.into();
+            }
+        }
+
+        impl<T> Div<T> for $size
+        where
+            T: Into<$inner>,
+        {
+            type Output = $size;
+            #[inline(always)]
+            fn div(self, rhs: T) -> $size {
+                $size(self.0 / rhs.into())
+            }
+        }
+
+        impl<T> DivAssign<T> for $size
+        where
+            T: Into<$inner>,
+        {
+            #[inline(always)]
+            fn div_assign(&mut self, rhs: T) {
+                self.0 /= rhs.into();
+            }
+        }
+
+        /// How many whole times `rhs` fits into the size.
+        impl Div<$size> for $size {
+            type Output = $inner;
+
+            #[inline(always)]
+            fn div(self, rhs: $size) -> $inner {
+                self.0 / rhs.0
+            }
+        }
+
+        impl Rem<$size> for $size {
+            type Output = $size;
+
+            #[inline(always)]
+            fn rem(self, rhs: $size) -> $size {
+                $size(self.0 % rhs.0)
+            }
+        }
+
+        impl RemAssign<$size> for $size {
+            #[inline(always)]
+            fn rem_assign(&mut self, rhs: $size) {
+                self.0 %= rhs.0
+            }
+        }
+    };
+}
+
+/// Computes `bytes * factor / divisor`, or `bytes / factor / divisor` when `invert` is set,
+/// exactly and rounded to the nearest byte with ties rounding up.
+///
+/// Returns `None` if `factor` is NaN, negative or infinite, if it is zero and `invert` is
+/// set, or if the result does not fit in a `u64`.
+pub(crate) fn scale(bytes: u64, factor: f64, divisor: u64, invert: bool) -> Option<u64> {
+    if !factor.is_finite() || factor < 0.0 {
+        return None;
+    }
+
+    // Every finite float is exactly `mantissa * 2^exponent`.
+    let bits = factor.to_bits();
+    let biased = ((bits >> 52) & 0x7ff) as i32;
+    let fraction = (bits & ((1 << 52) - 1)) as u128;
+    let (mantissa, exponent) = match biased {
+        0 => (fraction, -1074),
+        _ => (fraction | 1 << 52, biased - 1075),
+    };
+
+    let (mut numerator, mut denominator, shift) = if invert {
+        if mantissa == 0 {
+            return None;
+        }
+        (bytes as u128, mantissa * divisor as u128, -exponent)
+    } else {
+        (bytes as u128 * mantissa, divisor as u128, exponent)
+    };
+    if numerator == 0 {
+        return Some(0);
+    }
+
+    if shift >= 0 {
+        if shift as u32 > numerator.leading_zeros() {
+            return None;
+        }
+        numerator <<= shift;
+    } else {
+        // A denominator this far past the numerator rounds the quotient down to zero.
+        if shift.unsigned_abs() > denominator.leading_zeros() {
+            return Some(0);
+        }
+        denominator <<= shift.unsigned_abs();
+    }
+
+    let quotient = numerator / denominator;
+    let remainder = numerator % denominator;
+    let rounded = if remainder >= denominator - remainder {
+        quotient + 1
+    } else {
+        quotient
+    };
+    u64::try_from(rounded).ok()
+}
@@ -0,0 +1,364 @@
+use std::convert::TryFrom;
+use std::fmt::{self, Debug, Formatter};
+use std::num::TryFromIntError;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign};
+
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{ByteSize, Display, Unit, UnitSystem};
+
+/// Byte size representation backed by a `u128`, for totals beyond the range of [`ByteSize`]
+/// such as zettabyte-scale capacity accounting.
+#[derive(Copy, Clone, PartialEq, PartialOrd, Eq, Ord, Hash, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct ByteSize128(pub u128);
+
+impl ByteSize128 {
+    #[inline(always)]
+    pub const fn b(size: u128) -> ByteSize128 {
+        ByteSize128(size)
+    }
+
+    #[inline(always)]
+    pub const fn kb(size: u128) -> ByteSize128 {
+        ByteSize128(size * super::KB as u128)
+    }
+
+    #[inline(always)]
+    pub const fn kib(size: u128) -> ByteSize128 {
+        ByteSize128(size * super::KIB as u128)
+    }
+
+    #[inline(always)]
+    pub const fn mb(size: u128) -> ByteSize128 {
+        ByteSize128(size * super::MB as u128)
+    }
+
+    #[inline(always)]
+    pub const fn mib(size: u128) -> ByteSize128 {
+        ByteSize128(size * super::MIB as u128)
+    }
+
+    #[inline(always)]
+    pub const fn gb(size: u128) -> ByteSize128 {
+        ByteSize128(size * super::GB as u128)
+    }
+
+    #[inline(always)]
+    pub const fn gib(size: u128) -> ByteSize128 {
+        ByteSize128(size * super::GIB as u128)
+    }
+
+    #[inline(always)]
+    pub const fn tb(size: u128) -> ByteSize128 {
+        ByteSize128(size * super::TB as u128)
+    }
+
+    #[inline(always)]
+    pub const fn tib(size: u128) -> ByteSize128 {
+        ByteSize128(size * super::TIB as u128)
+    }
+
+    #[inline(always)]
+    pub const fn pb(size: u128) -> ByteSize128 {
+        ByteSize128(size * super::PB as u128)
+    }
+
+    #[inline(always)]
+    pub const fn pib(size: u128) -> ByteSize128 {
+        ByteSize128(size * super::PIB as u128)
+    }
+
+    #[inline(always)]
+    pub const fn eb(size: u128) -> ByteSize128 {
+        ByteSize128(size * super::EB as u128)
+    }
+
+    #[inline(always)]
+    pub const fn eib(size: u128) -> ByteSize128 {
+        ByteSize128(size * super::EIB as u128)
+    }
+
+    #[inline(always)]
+    pub const fn zb(size: u128) -> ByteSize128 {
+        ByteSize128(size * super::ZB)
+    }
+
+    #[inline(always)]
+    pub const fn zib(size: u128) -> ByteSize128 {
+        ByteSize128(size * super::ZIB)
+    }
+
+    #[inline(always)]
+    pub const fn yb(size: u128) -> ByteSize128 {
+        ByteSize128(size * super::YB)
+    }
+
+    #[inline(always)]
+    pub const fn yib(size: u128) -> ByteSize128 {
+        ByteSize128(size * super::YIB)
+    }
+
+    #[inline(always)]
+    pub const fn rb(size: u128) -> ByteSize128 {
+        ByteSize128(size * super::RB)
+    }
+
+    #[inline(always)]
+    pub const fn rib(size: u128) -> ByteSize128 {
+        ByteSize128(size * super::RIB)
+    }
+
+    #[inline(always)]
+    pub const fn qb(size: u128) -> ByteSize128 {
+        ByteSize128(size * super::QB)
+    }
+
+    #[inline(always)]
+    pub const fn qib(size: u128) -> ByteSize128 {
+        ByteSize128(size * super::QIB)
+    }
+
+    #[inline(always)]
+    pub const fn as_u128(&self) -> u128 {
+        self.0
+    }
+
+    #[inline(always)]
+    pub fn to_string_with(&self, system: UnitSystem) -> String {
+        self.display().system(system).to_string()
+    }
+
+    /// Renders the size in the given unit with a fixed number of decimal places.
+    #[inline(always)]
+    pub fn to_string_in(&self, unit: Unit, precision: usize) -> String {
+        self.display().unit(unit).precision(precision).to_string()
+    }
+
+    /// Returns the size as a fractional number of the given unit.
+    #[inline(always)]
+    pub fn as_unit_f64(&self, unit: Unit) -> f64 {
+        self.0 as f64 / unit.factor() as f64
+    }
+
+    /// Returns an adapter for rendering this size with custom formatting options.
+    #[inline(always)]
+    pub const fn display(&self) -> Display {
+        Display::new(self.0)
+    }
+}
+
+impl From<ByteSize> for ByteSize128 {
+    #[inline(always)]
+    fn from(size: ByteSize) -> ByteSize128 {
+        ByteSize128(u128::from(size.0))
+    }
+}
+
+impl TryFrom<ByteSize128> for ByteSize {
+    type Error = TryFromIntError;
+
+    #[inline(always)]
+    fn try_from(size: ByteSize128) -> Result<ByteSize, Self::Error> {
+        u64::try_from(size.0).map(ByteSize)
+    }
+}
+
+/// Formats the size in binary units, with `{:#}` switching to decimal units.
+///
+/// Precision (`{:.2}`) sets the number of decimal places and `{:+}` adds a leading plus sign.
+impl fmt::Display for ByteSize128 {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let display = if f.alternate() {
+            self.display().decimal()
+        } else {
+            self.display()
+        };
+        fmt::Display::fmt(&display, f)
+    }
+}
+
+impl Debug for ByteSize128 {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+radix_fmt!(ByteSize128);
+
+arithmetic!(ByteSize128(u128));
+
+operators!(ByteSize128(u128), u128, u64, u32, u16, u8);
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for ByteSize128 {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ByteSize128Vistor;
+
+        impl<'de> de::Visitor<'de> for ByteSize128Vistor {
+            type Value = ByteSize128;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("an integer or string")
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+                if let Ok(val) = u128::try_from(value) {
+                    Ok(ByteSize128(val))
+                } else {
+                    Err(E::invalid_value(
+                        de::Unexpected::Signed(value),
+                        &"integer overflow",
+                    ))
+                }
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+                Ok(ByteSize128(u128::from(value)))
+            }
+
+            fn visit_u128<E: de::Error>(self, value: u128) -> Result<Self::Value, E> {
+                Ok(ByteSize128(value))
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                if let Ok(val) = value.parse() {
+                    Ok(val)
+                } else {
+                    Err(E::invalid_value(
+                        de::Unexpected::Str(value),
+                        &"parsable string",
+                    ))
+                }
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(ByteSize128Vistor)
+        } else {
+            deserializer.deserialize_u128(ByteSize128Vistor)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for ByteSize128 {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            <str>::serialize(self.to_string().as_str(), serializer)
+        } else {
+            self.0.serialize(serializer)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic_op() {
+        let mut x = ByteSize128::zb(1);
+        let y = ByteSize128::eb(100);
+
+        assert_eq!((x + y).as_u128(), 1_100_000_000_000_000_000_000);
+        assert_eq!((x - y).as_u128(), 900_000_000_000_000_000_000);
+        assert_eq!((x * 2u64).as_u128(), 2_000_000_000_000_000_000_000);
+        assert_eq!((3u8 * x).as_u128(), 3_000_000_000_000_000_000_000);
+
+        x += y;
+        assert_eq!(x.as_u128(), 1_100_000_000_000_000_000_000);
+        x -= 100_000_000_000_000_000_000u128;
+        assert_eq!(x, ByteSize128::zb(1));
+        x *= 1000u32;
+        assert_eq!(x, ByteSize128::yb(1));
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let max = ByteSize128(u128::MAX);
+        assert_eq!(ByteSize128::zib(1).checked_sub(ByteSize128::zib(2)), None);
+        assert_eq!(max.checked_add(ByteSize128(1)), None);
+        assert_eq!(max.saturating_mul(2), max);
+        assert_eq!(ByteSize128(0).wrapping_sub_bytes(1), max);
+        assert_eq!(
+            ByteSize128::zib(1).abs_diff(ByteSize128::zib(3)),
+            ByteSize128::zib(2)
+        );
+
+        let total = ByteSize128::zib(10);
+        assert_eq!(total / 4u8, ByteSize128::eib(2560));
+        assert_eq!(total / ByteSize128::zib(3), 3);
+        assert_eq!(total % ByteSize128::zib(3), ByteSize128::zib(1));
+        assert_eq!(total.div_ceil(ByteSize128::zib(3)), 4);
+        assert_eq!(total.checked_div(0), None);
+        assert_eq!(total.checked_rem(ByteSize128(0)), None);
+        assert_eq!(ByteSize128::zib(1).ratio(ByteSize128::zib(4)), 0.25);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!("215 B", ByteSize128::b(215).to_string());
+        assert_eq!("1.0 EiB", ByteSize128::eib(1).to_string());
+        assert_eq!("5.0 ZiB", ByteSize128::zib(5).to_string());
+        assert_eq!("7.0 YiB", ByteSize128::yib(7).to_string());
+        assert_eq!("1.0 RiB", ByteSize128::rib(1).to_string());
+        assert_eq!("1023.0 QiB", ByteSize128::qib(1023).to_string());
+        assert_eq!("268435456.0 QiB", ByteSize128(u128::MAX).to_string());
+
+        assert_eq!("1.2 ZB", format!("{:#}", ByteSize128::eb(1200)));
+        assert_eq!("340282366.92 QB", format!("{:#.2}", ByteSize128(u128::MAX)));
+        assert_eq!(
+            "4.0 YB",
+            ByteSize128::yb(4).to_string_with(UnitSystem::Decimal)
+        );
+        assert_eq!("4000 EB", ByteSize128::zb(4).to_string_in(Unit::EB, 0));
+        assert_eq!(2.0, ByteSize128::zib(2).as_unit_f64(Unit::ZiB));
+
+        assert_eq!(
+            "0x1000000000000000000",
+            format!("{:#x}", ByteSize128::zib(4))
+        );
+        assert_eq!(
+            "100000000000000000000",
+            format!("{:X}", ByteSize128::yib(1))
+        );
+    }
+
+    #[test]
+    fn test_conversion() {
+        let small = ByteSize::gib(5);
+        assert_eq!(ByteSize128::gib(5), ByteSize128::from(small));
+        assert_eq!(Ok(small), ByteSize::try_from(ByteSize128::gib(5)));
+        assert_eq!(
+            Ok(ByteSize(u64::MAX)),
+            ByteSize::try_from(ByteSize128(u128::from(u64::MAX)))
+        );
+        assert!(ByteSize::try_from(ByteSize128::zb(1)).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        #[derive(Serialize, Deserialize)]
+        struct S {
+            x: ByteSize128,
+        }
+
+        let s: S = serde_json::from_str(r#"{ "x": "5 ZiB" }"#).unwrap();
+        assert_eq!(s.x, ByteSize128::zib(5));
+
+        let s: S = serde_json::from_str(r#"{ "x": 1048576 }"#).unwrap();
+        assert_eq!(s.x, ByteSize128::mib(1));
+
+        let json = serde_json::to_string(&ByteSize128::yib(3)).unwrap();
+        assert_eq!(json, "\"3.0 YiB\"");
+    }
+}
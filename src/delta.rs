@@ -0,0 +1,429 @@
+use std::fmt::{self, Debug, Formatter};
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{ByteSize, Display};
+
+/// Signed difference between two sizes, such as growth or shrinkage between two releases.
+///
+/// Displays with an explicit sign, in binary units unless `{:#}` asks for decimal ones.
+///
+/// ```
+/// use bytesize::{ByteSize, ByteSizeDelta};
+///
+/// let delta = ByteSizeDelta::between(ByteSize::mib(10), ByteSize::kib(9900));
+/// assert_eq!("-340.0 KiB", delta.to_string());
+/// assert_eq!(delta, "-340 KiB".parse().unwrap());
+/// ```
+#[derive(Copy, Clone, PartialEq, PartialOrd, Eq, Ord, Hash, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct ByteSizeDelta(pub i64);
+
+impl ByteSizeDelta {
+    /// Change from `old` to `new`, saturating at the bounds of an `i64`.
+    #[inline(always)]
+    pub const fn between(old: ByteSize, new: ByteSize) -> ByteSizeDelta {
+        let delta = new.0 as i128 - old.0 as i128;
+        if delta > i64::MAX as i128 {
+            ByteSizeDelta(i64::MAX)
+        } else if delta < i64::MIN as i128 {
+            ByteSizeDelta(i64::MIN)
+        } else {
+            ByteSizeDelta(delta as i64)
+        }
+    }
+
+    /// Change from `old` to `new`, returning `None` if it does not fit in an `i64`.
+    #[inline(always)]
+    pub const fn checked_between(old: ByteSize, new: ByteSize) -> Option<ByteSizeDelta> {
+        let delta = new.0 as i128 - old.0 as i128;
+        if delta > i64::MAX as i128 || delta < i64::MIN as i128 {
+            None
+        } else {
+            Some(ByteSizeDelta(delta as i64))
+        }
+    }
+
+    #[inline(always)]
+    pub const fn as_i64(&self) -> i64 {
+        self.0
+    }
+
+    /// Size of the change regardless of its direction.
+    #[inline(always)]
+    pub const fn magnitude(&self) -> ByteSize {
+        ByteSize(self.0.unsigned_abs())
+    }
+
+    /// Whether the size grew.
+    #[inline(always)]
+    pub const fn is_positive(&self) -> bool {
+        self.0 > 0
+    }
+
+    /// Whether the size shrank.
+    #[inline(always)]
+    pub const fn is_negative(&self) -> bool {
+        self.0 < 0
+    }
+
+    /// Adds `rhs`, returning `None` on overflow.
+    #[inline(always)]
+    pub const fn checked_add(self, rhs: ByteSizeDelta) -> Option<ByteSizeDelta> {
+        match self.0.checked_add(rhs.0) {
+            Some(bytes) => Some(ByteSizeDelta(bytes)),
+            None => None,
+        }
+    }
+
+    /// Subtracts `rhs`, returning `None` on overflow.
+    #[inline(always)]
+    pub const fn checked_sub(self, rhs: ByteSizeDelta) -> Option<ByteSizeDelta> {
+        match self.0.checked_sub(rhs.0) {
+            Some(bytes) => Some(ByteSizeDelta(bytes)),
+            None => None,
+        }
+    }
+
+    /// Adds `rhs`, saturating at the bounds of an `i64`.
+    #[inline(always)]
+    pub const fn saturating_add(self, rhs: ByteSizeDelta) -> ByteSizeDelta {
+        ByteSizeDelta(self.0.saturating_add(rhs.0))
+    }
+
+    /// Subtracts `rhs`, saturating at the bounds of an `i64`.
+    #[inline(always)]
+    pub const fn saturating_sub(self, rhs: ByteSizeDelta) -> ByteSizeDelta {
+        ByteSizeDelta(self.0.saturating_sub(rhs.0))
+    }
+
+    /// Returns an adapter for rendering this delta with custom formatting options.
+    ///
+    /// The sign is always written, except for a zero delta.
+    #[inline(always)]
+    pub const fn display(&self) -> Display {
+        Display::signed(self.0)
+    }
+}
+
+impl ByteSize {
+    /// Applies a change in size, returning `None` if the result would be negative or overflow.
+    #[inline(always)]
+    pub const fn checked_add_delta(self, delta: ByteSizeDelta) -> Option<ByteSize> {
+        if delta.0 >= 0 {
+            self.checked_add_bytes(delta.0 as u64)
+        } else {
+            self.checked_sub_bytes(delta.0.unsigned_abs())
+        }
+    }
+
+    /// Applies a change in size, saturating at zero and the largest size.
+    #[inline(always)]
+    pub const fn saturating_add_delta(self, delta: ByteSizeDelta) -> ByteSize {
+        if delta.0 >= 0 {
+            self.saturating_add_bytes(delta.0 as u64)
+        } else {
+            self.saturating_sub_bytes(delta.0.unsigned_abs())
+        }
+    }
+}
+
+/// Formats the delta with its sign in binary units, with `{:#}` switching to decimal units.
+///
+/// Precision (`{:.2}`) sets the number of decimal places.
+impl fmt::Display for ByteSizeDelta {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let display = if f.alternate() {
+            self.display().decimal()
+        } else {
+            self.display()
+        };
+        fmt::Display::fmt(&display, f)
+    }
+}
+
+impl Debug for ByteSizeDelta {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl Add<ByteSizeDelta> for ByteSizeDelta {
+    type Output = ByteSizeDelta;
+
+    #[inline(always)]
+    fn add(self, rhs: ByteSizeDelta) -> ByteSizeDelta {
+        ByteSizeDelta(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign<ByteSizeDelta> for ByteSizeDelta {
+    #[inline(always)]
+    fn add_assign(&mut self, rhs: ByteSizeDelta) {
+        self.0 += rhs.0
+    }
+}
+
+impl Sub<ByteSizeDelta> for ByteSizeDelta {
+    type Output = ByteSizeDelta;
+
+    #[inline(always)]
+    fn sub(self, rhs: ByteSizeDelta) -> ByteSizeDelta {
+        ByteSizeDelta(self.0 - rhs.0)
+    }
+}
+
+impl SubAssign<ByteSizeDelta> for ByteSizeDelta {
+    #[inline(always)]
+    fn sub_assign(&mut self, rhs: ByteSizeDelta) {
+        self.0 -= rhs.0
+    }
+}
+
+impl<T> Mul<T> for ByteSizeDelta
+where
+    T: Into<i64>,
+{
+    type Output = ByteSizeDelta;
+    #[inline(always)]
+    fn mul(self, rhs: T) -> ByteSizeDelta {
+        ByteSizeDelta(self.0 * rhs.into())
+    }
+}
+
+impl<T> MulAssign<T> for ByteSizeDelta
+where
+    T: Into<i64>,
+{
+    #[inline(always)]
+    fn mul_assign(&mut self, rhs: T) {
+        self.0 *= rhs.into();
+    }
+}
+
+impl Neg for ByteSizeDelta {
+    type Output = ByteSizeDelta;
+
+    #[inline(always)]
+    fn neg(self) -> ByteSizeDelta {
+        ByteSizeDelta(-self.0)
+    }
+}
+
+/// Applies a change in size.
+///
+/// # Panics
+///
+/// Panics if the result would be negative or overflow, like `ByteSize - ByteSize`.
+impl Add<ByteSizeDelta> for ByteSize {
+    type Output = ByteSize;
+
+    #[inline(always)]
+    fn add(self, rhs: ByteSizeDelta) -> ByteSize {
+        self.checked_add_delta(rhs)
+            .expect("attempt to apply a size delta with overflow")
+    }
+}
+
+impl AddAssign<ByteSizeDelta> for ByteSize {
+    #[inline(always)]
+    fn add_assign(&mut self, rhs: ByteSizeDelta) {
+        *self = *self + rhs;
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for ByteSizeDelta {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ByteSizeDeltaVistor;
+
+        impl<'de> de::Visitor<'de> for ByteSizeDeltaVistor {
+            type Value = ByteSizeDelta;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("an integer or string")
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+                Ok(ByteSizeDelta(value))
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+                if let Ok(val) = i64::try_from(value) {
+                    Ok(ByteSizeDelta(val))
+                } else {
+                    Err(E::invalid_value(
+                        de::Unexpected::Unsigned(value),
+                        &"integer overflow",
+                    ))
+                }
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                if let Ok(val) = value.parse() {
+                    Ok(val)
+                } else {
+                    Err(E::invalid_value(
+                        de::Unexpected::Str(value),
+                        &"parsable string",
+                    ))
+                }
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(ByteSizeDeltaVistor)
+        } else {
+            deserializer.deserialize_i64(ByteSizeDeltaVistor)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for ByteSizeDelta {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            <str>::serialize(self.to_string().as_str(), serializer)
+        } else {
+            self.0.serialize(serializer)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UnitStyle;
+
+    #[test]
+    fn test_between() {
+        let old = ByteSize::mib(10);
+        let new = ByteSize::kib(9900);
+
+        assert_eq!(ByteSizeDelta::between(old, new), ByteSizeDelta(-348_160));
+        assert_eq!(ByteSizeDelta::between(new, old), ByteSizeDelta(348_160));
+        assert_eq!(ByteSizeDelta::between(old, old), ByteSizeDelta(0));
+        assert_eq!(
+            ByteSizeDelta::between(ByteSize(0), ByteSize(u64::MAX)),
+            ByteSizeDelta(i64::MAX)
+        );
+        assert_eq!(
+            ByteSizeDelta::between(ByteSize(u64::MAX), ByteSize(0)),
+            ByteSizeDelta(i64::MIN)
+        );
+        assert_eq!(
+            ByteSizeDelta::checked_between(ByteSize(u64::MAX), ByteSize(0)),
+            None
+        );
+        assert_eq!(
+            ByteSizeDelta::checked_between(ByteSize(1 << 63), ByteSize(0)),
+            Some(ByteSizeDelta(i64::MIN))
+        );
+
+        let delta = ByteSizeDelta::between(old, new);
+        assert_eq!(delta.magnitude(), ByteSize::kib(340));
+        assert!(delta.is_negative());
+        assert!(!delta.is_positive());
+        assert_eq!(ByteSizeDelta(i64::MIN).magnitude(), ByteSize(1 << 63));
+    }
+
+    #[test]
+    fn test_arithmetic_op() {
+        let mut x = ByteSizeDelta(1024);
+        let y = ByteSizeDelta(-4096);
+
+        assert_eq!(x + y, ByteSizeDelta(-3072));
+        assert_eq!(x - y, ByteSizeDelta(5120));
+        assert_eq!(y * -2, ByteSizeDelta(8192));
+        assert_eq!(-y, ByteSizeDelta(4096));
+
+        x += y;
+        assert_eq!(x, ByteSizeDelta(-3072));
+        x -= y;
+        assert_eq!(x, ByteSizeDelta(1024));
+        x *= 3i32;
+        assert_eq!(x, ByteSizeDelta(3072));
+
+        assert_eq!(ByteSizeDelta(i64::MAX).checked_add(x), None);
+        assert_eq!(ByteSizeDelta(i64::MIN).checked_sub(x), None);
+        assert_eq!(x.checked_sub(y), Some(ByteSizeDelta(7168)));
+        assert_eq!(
+            ByteSizeDelta(i64::MIN).saturating_add(y),
+            ByteSizeDelta(i64::MIN)
+        );
+        assert_eq!(
+            ByteSizeDelta(i64::MAX).saturating_sub(y),
+            ByteSizeDelta(i64::MAX)
+        );
+
+        let mut size = ByteSize::kib(4);
+        assert_eq!(size + ByteSizeDelta(-1024), ByteSize::kib(3));
+        size += ByteSizeDelta(1024);
+        assert_eq!(size, ByteSize::kib(5));
+        assert_eq!(size.checked_add_delta(ByteSizeDelta(-6 * 1024)), None);
+        assert_eq!(
+            size.saturating_add_delta(ByteSizeDelta(-6 * 1024)),
+            ByteSize(0)
+        );
+        assert_eq!(
+            ByteSize(u64::MAX).saturating_add_delta(ByteSizeDelta(1)),
+            ByteSize(u64::MAX)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "size delta with overflow")]
+    fn test_add_delta_overflow() {
+        let _ = ByteSize::kib(1) + ByteSizeDelta(-2048);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!("+1.2 MiB", ByteSizeDelta(1_258_291).to_string());
+        assert_eq!("-340.0 KiB", ByteSizeDelta(-348_160).to_string());
+        assert_eq!("0 B", ByteSizeDelta(0).to_string());
+        assert_eq!("-1 B", ByteSizeDelta(-1).to_string());
+        assert_eq!("+8.0 EiB", ByteSizeDelta(i64::MAX).to_string());
+        assert_eq!("-8.0 EiB", ByteSizeDelta(i64::MIN).to_string());
+
+        assert_eq!("+1.5 MB", format!("{:#}", ByteSizeDelta(1_500_000)));
+        assert_eq!("-1.50 MB", format!("{:#.2}", ByteSizeDelta(-1_500_000)));
+        assert_eq!("|  -1.0 KiB|", format!("|{:>10}|", ByteSizeDelta(-1024)));
+        assert_eq!("-1.0 KiB", format!("{:+}", ByteSizeDelta(-1024)));
+        assert_eq!(
+            "-2K",
+            ByteSizeDelta(-2048)
+                .display()
+                .style(UnitStyle::Terse)
+                .precision(0)
+                .space(false)
+                .to_string()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        #[derive(Serialize, Deserialize)]
+        struct S {
+            x: ByteSizeDelta,
+        }
+
+        let s: S = serde_json::from_str(r#"{ "x": "-512 MiB" }"#).unwrap();
+        assert_eq!(s.x, ByteSizeDelta(-512 * 1024 * 1024));
+
+        let s: S = serde_json::from_str(r#"{ "x": -1024 }"#).unwrap();
+        assert_eq!(s.x, ByteSizeDelta(-1024));
+
+        let json = serde_json::to_string(&ByteSizeDelta(3 << 30)).unwrap();
+        assert_eq!(json, "\"+3.0 GiB\"");
+    }
+}
@@ -0,0 +1,1118 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Write as _};
+
+use super::{Unit, UnitSystem};
+
+static UNITS: &str = "KMGTPEZYRQ";
+
+/// Upper bound on the fraction digits that can be non-zero: any quotient by a power of
+/// 1000 or 1024 up to quetta terminates after at most 100 decimal places.
+const FRACTION_DIGITS: usize = 100;
+
+/// Separator written between the number and the unit.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum Separator {
+    /// No separator: `1.5KiB`.
+    None,
+    /// A regular space: `1.5 KiB`.
+    #[default]
+    Space,
+    /// A non-breaking space (U+00A0), which keeps number and unit on one line.
+    NonBreakingSpace,
+}
+
+impl Separator {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Separator::None => "",
+            Separator::Space => " ",
+            Separator::NonBreakingSpace => "\u{a0}",
+        }
+    }
+}
+
+/// How the unit of a formatted size is spelled.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum UnitStyle {
+    /// Full unit symbol: `KiB`, `MB`.
+    #[default]
+    Symbol,
+    /// Unit prefix without the trailing `B`: `Ki`, `M`. Plain bytes are still written as `B`.
+    Short,
+    /// Single-letter suffix as printed by `ls -h` and `du -h`: `K`, `M` (`k` for decimal kilo).
+    /// Plain bytes get no suffix and no separator.
+    Terse,
+    /// Spelled-out unit name, singular only for exactly one: `1 byte`, `1.5 mebibytes`.
+    Long,
+}
+
+/// Rounding applied to the last written digit of a scaled size.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum Rounding {
+    /// Round towards zero; never overstates a size.
+    Floor,
+    /// Round away from zero; never understates a size.
+    Ceil,
+    /// Round to nearest, ties away from zero.
+    HalfUp,
+    /// Round to nearest, ties to the even digit.
+    #[default]
+    HalfEven,
+}
+
+/// Formatting adapter returned by [`ByteSize::display()`](crate::ByteSize::display),
+/// [`ByteSize128::display()`](crate::ByteSize128::display) and
+/// [`ByteSizeDelta::display()`](crate::ByteSizeDelta::display).
+///
+/// The adapter writes straight into the [`fmt::Formatter`] and honors its width, fill and
+/// alignment. A precision given in the format string (`{:.3}`) takes priority over
+/// [`Display::precision()`], and the `+` flag prefixes the output with a plus sign.
+///
+/// ```
+/// use bytesize::{ByteSize, Separator};
+///
+/// let size = ByteSize::kb(1500);
+/// assert_eq!("1.4 MiB", size.display().to_string());
+/// assert_eq!("1.50MB", size.display().decimal().precision(2).space(false).to_string());
+/// assert_eq!("1.5\u{a0}MB", size.display().decimal().separator(Separator::NonBreakingSpace).to_string());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Display {
+    bytes: u128,
+    system: UnitSystem,
+    unit: Option<Unit>,
+    precision: usize,
+    significant: Option<usize>,
+    rounding: Rounding,
+    separator: Separator,
+    style: UnitStyle,
+    bits: bool,
+    breakdown: Option<usize>,
+    sign: Option<char>,
+}
+
+impl Display {
+    #[inline(always)]
+    pub(crate) const fn new(bytes: u128) -> Display {
+        Display {
+            bytes,
+            system: UnitSystem::Binary,
+            unit: None,
+            precision: 1,
+            significant: None,
+            rounding: Rounding::HalfEven,
+            separator: Separator::Space,
+            style: UnitStyle::Symbol,
+            bits: false,
+            breakdown: None,
+            sign: None,
+        }
+    }
+
+    /// Formats the magnitude of a signed byte count, always prefixed with its sign unless zero.
+    #[inline(always)]
+    pub(crate) const fn signed(bytes: i64) -> Display {
+        let mut display = Display::new(bytes.unsigned_abs() as u128);
+        display.sign = match bytes {
+            0 => None,
+            1.. => Some('+'),
+            _ => Some('-'),
+        };
+        display
+    }
+
+    /// Use the given unit system.
+    #[inline(always)]
+    pub const fn system(mut self, system: UnitSystem) -> Display {
+        self.system = system;
+        self
+    }
+
+    /// Use decimal (SI) units: KB, MB, GB, ...
+    #[inline(always)]
+    pub const fn decimal(self) -> Display {
+        self.system(UnitSystem::Decimal)
+    }
+
+    /// Use binary (IEC) units: KiB, MiB, GiB, ... This is the default.
+    #[inline(always)]
+    pub const fn binary(self) -> Display {
+        self.system(UnitSystem::Binary)
+    }
+
+    /// Use JEDEC units, where KB means 1024 bytes.
+    #[inline(always)]
+    pub const fn jedec(self) -> Display {
+        self.system(UnitSystem::Jedec)
+    }
+
+    /// Always render in the given unit instead of choosing one automatically, e.g. to show
+    /// every row of a table in GiB. Overrides the unit system, except that binary units keep
+    /// their JEDEC spelling under [`UnitSystem::Jedec`], which shares their factors.
+    ///
+    /// ```
+    /// use bytesize::{ByteSize, Unit};
+    ///
+    /// assert_eq!("0.5 GiB", ByteSize::mib(512).display().unit(Unit::GiB).to_string());
+    /// assert_eq!("1024.0 GiB", ByteSize::tib(1).display().unit(Unit::GiB).to_string());
+    /// assert_eq!("0.5 GB", ByteSize::mib(512).display().jedec().unit(Unit::GiB).to_string());
+    /// ```
+    #[inline(always)]
+    pub const fn unit(mut self, unit: Unit) -> Display {
+        self.unit = Some(unit);
+        self
+    }
+
+    /// Number of decimal places written for scaled values. Defaults to 1.
+    #[inline(always)]
+    pub const fn precision(mut self, precision: usize) -> Display {
+        self.precision = precision;
+        self.significant = None;
+        self
+    }
+
+    /// Write scaled values with at most `digits` significant digits instead of a fixed number
+    /// of decimal places. Integer digits are never dropped, so `significant(2)` renders
+    /// "1.5 KiB", "23 KiB" and "512 MiB".
+    ///
+    /// Combined with [`Rounding::Ceil`] and [`UnitStyle::Terse`] this matches GNU `ls -h`:
+    ///
+    /// ```
+    /// use bytesize::{ByteSize, Rounding, UnitStyle};
+    ///
+    /// let ls = |size: ByteSize| {
+    ///     size.display()
+    ///         .significant(2)
+    ///         .rounding(Rounding::Ceil)
+    ///         .style(UnitStyle::Terse)
+    ///         .space(false)
+    ///         .to_string()
+    /// };
+    /// assert_eq!("1.5K", ls(ByteSize::b(1536)));
+    /// assert_eq!("23K", ls(ByteSize::kib(23)));
+    /// assert_eq!("512M", ls(ByteSize::mib(512)));
+    /// assert_eq!("817", ls(ByteSize::b(817)));
+    /// ```
+    #[inline(always)]
+    pub const fn significant(mut self, digits: usize) -> Display {
+        self.significant = Some(digits);
+        self
+    }
+
+    /// Rounding of the last decimal place. Defaults to [`Rounding::HalfEven`].
+    #[inline(always)]
+    pub const fn rounding(mut self, rounding: Rounding) -> Display {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Separator between number and unit. Defaults to [`Separator::Space`].
+    #[inline(always)]
+    pub const fn separator(mut self, separator: Separator) -> Display {
+        self.separator = separator;
+        self
+    }
+
+    /// Shorthand for choosing between [`Separator::Space`] and [`Separator::None`].
+    #[inline(always)]
+    pub const fn space(self, space: bool) -> Display {
+        self.separator(if space {
+            Separator::Space
+        } else {
+            Separator::None
+        })
+    }
+
+    /// Spelling of the unit. Defaults to [`UnitStyle::Symbol`].
+    #[inline(always)]
+    pub const fn style(mut self, style: UnitStyle) -> Display {
+        self.style = style;
+        self
+    }
+
+    /// Write the size in bits rather than bytes: `8 bit`, `1.5 Mbit`, `12.0 Gibit`.
+    ///
+    /// Decimal kilo is written `kbit`, JEDEC kilo `Kbit`. [`UnitStyle::Short`] writes `Mb` and
+    /// `Mib`, [`UnitStyle::Long`] writes `megabits`.
+    #[inline(always)]
+    pub const fn bits(mut self) -> Display {
+        self.bits = true;
+        self
+    }
+
+    /// Split the size into whole components of descending units: `1 GiB 512 MiB 3 KiB`.
+    ///
+    /// At most `limit` components are written, dropping whatever is left below the last one;
+    /// without a limit the output is exact and parses back to the same size, given the
+    /// JEDEC [`system()`](crate::ParseOptions::system) for JEDEC units. Precision, rounding
+    /// and a fixed [`unit()`](Display::unit) do not apply to a breakdown.
+    ///
+    /// Units that would parse back as something else are written unambiguously: the terse
+    /// style writes `Ki`, `Mi`, ... for binary units and `kb`, `Mb`, ... for bits, decimal
+    /// prefixes keep their `B` when there is no separator, and the long style spells out
+    /// JEDEC units by their binary names.
+    ///
+    /// ```
+    /// use bytesize::ByteSize;
+    ///
+    /// let size = ByteSize::gib(1) + ByteSize::mib(512) + ByteSize::kib(3);
+    /// assert_eq!("1 GiB 512 MiB 3 KiB", size.display().breakdown(None).to_string());
+    /// assert_eq!("1 GiB 512 MiB", size.display().breakdown(Some(2)).to_string());
+    /// assert_eq!(size, "1 GiB 512 MiB 3 KiB".parse().unwrap());
+    /// ```
+    #[inline(always)]
+    pub const fn breakdown(mut self, limit: Option<usize>) -> Display {
+        self.breakdown = match limit {
+            Some(limit) => Some(limit),
+            None => Some(usize::MAX),
+        };
+        self
+    }
+
+    fn render<W: fmt::Write>(&self, w: &mut W, sign_plus: bool) -> fmt::Result {
+        match self.sign {
+            Some(sign) => w.write_char(sign)?,
+            None if sign_plus => w.write_char('+')?,
+            None => {}
+        }
+        if let Some(limit) = self.breakdown {
+            return self.render_breakdown(w, limit);
+        }
+
+        let (system, exp, scaled) = self.scaled();
+        let (value, precision) = match scaled {
+            Some(scaled) => scaled,
+            None => {
+                let count = self.bytes.saturating_mul(if self.bits { 8 } else { 1 });
+                write!(w, "{}", count)?;
+                if self.style == UnitStyle::Terse {
+                    return Ok(());
+                }
+                w.write_str(self.separator.as_str())?;
+                return self.write_unit(w, system, 0, count == 1);
+            }
+        };
+        value.write(w, precision)?;
+        w.write_str(self.separator.as_str())?;
+        self.write_unit(w, system, exp, precision == 0 && value.integer == 1)
+    }
+
+    /// The unit this size is written in, as picked after rounding.
+    pub(crate) fn picked_unit(&self) -> Unit {
+        let (system, exp, _) = self.scaled();
+        Unit::from_exponent(system, exp)
+    }
+
+    /// Picks the unit system and exponent to write the size in, along with the rounded value
+    /// and its decimal places unless it is written as a plain count of bytes or bits.
+    fn scaled(&self) -> (UnitSystem, usize, Option<(Fixed, usize)>) {
+        let bytes = self.bytes;
+        // a byte is eight bits, so bit units divide by an eighth of the byte factor
+        let per_byte = if self.bits { 8 } else { 1 };
+        let (system, mut exp) = match self.unit {
+            Some(unit) => match (unit.system(), self.system) {
+                (UnitSystem::Binary, UnitSystem::Jedec) => (UnitSystem::Jedec, unit.exponent()),
+                (system, _) => (system, unit.exponent()),
+            },
+            None => (
+                self.system,
+                exponent(bytes.saturating_mul(per_byte), self.system),
+            ),
+        };
+        let base = u128::from(system.base());
+        let mut divisor = base.pow(exp as u32) / per_byte;
+
+        if exp == 0 {
+            return (system, exp, None);
+        }
+
+        // rounding may carry the value up to the base, e.g. 1023.96 KiB to "1024.0 KiB"
+        let (mut value, mut precision) = self.scale(bytes, divisor);
+        while self.unit.is_none() && value.integer >= base && exp < UNITS.len() {
+            divisor *= base;
+            exp += 1;
+            (value, precision) = self.scale(bytes, divisor);
+        }
+        (system, exp, Some((value, precision)))
+    }
+
+    fn render_breakdown<W: fmt::Write>(&self, w: &mut W, limit: usize) -> fmt::Result {
+        // single letters read as decimal bytes, and JEDEC names as decimal units
+        let (system, style) = match (self.system, self.style) {
+            (UnitSystem::Binary | UnitSystem::Jedec, UnitStyle::Terse) => {
+                (UnitSystem::Binary, UnitStyle::Short)
+            }
+            (system, UnitStyle::Terse) if self.bits => (system, UnitStyle::Short),
+            // a bare `E` right before the next number reads as an exponent: "1E512P"
+            (system, UnitStyle::Short | UnitStyle::Terse)
+                if system != UnitSystem::Binary
+                    && !self.bits
+                    && self.separator == Separator::None =>
+            {
+                (system, UnitStyle::Symbol)
+            }
+            (UnitSystem::Jedec, UnitStyle::Long) => (UnitSystem::Binary, UnitStyle::Long),
+            unchanged => unchanged,
+        };
+        let this = Display {
+            system,
+            style,
+            ..*self
+        };
+
+        let per_byte = if self.bits { 8 } else { 1 };
+        let base = u128::from(system.base());
+        // saturating only affects counts past the top unit, which stays the top unit
+        let mut exp = exponent(self.bytes.saturating_mul(per_byte), system);
+        let mut divisor = base.pow(exp as u32);
+        // the bit count may not fit a `u128`, so the remainder is kept in bytes: every unit
+        // above a single bit is a whole number of bytes
+        let mut rest = self.bytes;
+        let mut written = 0;
+
+        loop {
+            let value = if exp == 0 {
+                rest * per_byte
+            } else {
+                let unit_bytes = divisor / per_byte;
+                let value = rest / unit_bytes;
+                rest %= unit_bytes;
+                value
+            };
+            // zero still needs one component: "0 B"
+            if value > 0 || written == 0 && exp == 0 {
+                if written > 0 {
+                    w.write_str(self.separator.as_str())?;
+                }
+                write!(w, "{}", value)?;
+                if exp > 0 || style != UnitStyle::Terse {
+                    w.write_str(self.separator.as_str())?;
+                    this.write_unit(w, system, exp, value == 1)?;
+                }
+                written += 1;
+            }
+            if exp == 0 || rest == 0 || written >= limit.max(1) {
+                return Ok(());
+            }
+            divisor /= base;
+            exp -= 1;
+        }
+    }
+
+    /// Divides `bytes` by the unit `divisor`, returning the rounded value and its decimal places.
+    fn scale(&self, bytes: u128, divisor: u128) -> (Fixed, usize) {
+        let digits = match self.significant {
+            Some(digits) => digits,
+            None => {
+                return (
+                    Fixed::new(bytes, divisor, self.precision, self.rounding),
+                    self.precision,
+                )
+            }
+        };
+
+        let integer_digits = |mut integer: u128| {
+            let mut count = 1;
+            while integer >= 10 {
+                integer /= 10;
+                count += 1;
+            }
+            count
+        };
+        let mut precision = digits.saturating_sub(integer_digits(bytes / divisor));
+        let mut value = Fixed::new(bytes, divisor, precision, self.rounding);
+
+        // rounding may add an integer digit, e.g. 9.96 to "10.0"
+        if precision > 0 && integer_digits(value.integer) + precision > digits {
+            precision -= 1;
+            value = Fixed::new(bytes, divisor, precision, self.rounding);
+        }
+
+        (value, precision)
+    }
+
+    fn write_unit<W: fmt::Write>(
+        &self,
+        w: &mut W,
+        system: UnitSystem,
+        exp: usize,
+        singular: bool,
+    ) -> fmt::Result {
+        if self.style == UnitStyle::Long {
+            // JEDEC names its units kilobyte, megabyte, ... like the decimal ones
+            let system = match system {
+                UnitSystem::Jedec => UnitSystem::Decimal,
+                system => system,
+            };
+            let name = Unit::from_exponent(system, exp).name();
+            if self.bits {
+                w.write_str(name.trim_end_matches("byte"))?;
+                w.write_str("bit")?;
+            } else {
+                w.write_str(name)?;
+            }
+            return if singular { Ok(()) } else { w.write_char('s') };
+        }
+        if exp == 0 {
+            return w.write_str(if self.bits { "bit" } else { "B" });
+        }
+
+        let prefix = match (system, self.style, exp) {
+            // SI writes kilo in lower case when it stands alone
+            (UnitSystem::Decimal, UnitStyle::Terse, 1) => 'k',
+            (UnitSystem::Decimal, _, 1) if self.bits => 'k',
+            _ => UNITS.as_bytes()[exp - 1] as char,
+        };
+        let suffix = match (system, self.style) {
+            (_, UnitStyle::Terse) => "",
+            (UnitSystem::Binary, UnitStyle::Symbol) if self.bits => "ibit",
+            (UnitSystem::Binary, UnitStyle::Short) if self.bits => "ib",
+            (UnitSystem::Decimal | UnitSystem::Jedec, UnitStyle::Symbol) if self.bits => "bit",
+            (UnitSystem::Decimal | UnitSystem::Jedec, UnitStyle::Short) if self.bits => "b",
+            (UnitSystem::Binary, UnitStyle::Symbol) => "iB",
+            (UnitSystem::Binary, UnitStyle::Short) => "i",
+            (UnitSystem::Decimal | UnitSystem::Jedec, UnitStyle::Symbol) => "B",
+            (UnitSystem::Decimal | UnitSystem::Jedec, UnitStyle::Short) => "",
+            (_, UnitStyle::Long) => unreachable!("long names are written above"),
+        };
+
+        w.write_char(prefix)?;
+        w.write_str(suffix)
+    }
+}
+
+impl fmt::Display for Display {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut this = *self;
+        if let Some(precision) = f.precision() {
+            this = this.precision(precision);
+        }
+        let sign_plus = f.sign_plus();
+
+        let width = match f.width() {
+            Some(width) => width,
+            None => return this.render(f, sign_plus),
+        };
+
+        // measure first so padding can be written without an intermediate `String`
+        let mut counter = CharCounter(0);
+        this.render(&mut counter, sign_plus)?;
+        let padding = width.saturating_sub(counter.0);
+
+        let (pre, post) = match f.align() {
+            Some(fmt::Alignment::Right) => (padding, 0),
+            Some(fmt::Alignment::Center) => (padding / 2, padding - padding / 2),
+            Some(fmt::Alignment::Left) | None => (0, padding),
+        };
+        let fill = f.fill();
+
+        for _ in 0..pre {
+            f.write_char(fill)?;
+        }
+        this.render(f, sign_plus)?;
+        for _ in 0..post {
+            f.write_char(fill)?;
+        }
+        Ok(())
+    }
+}
+
+/// Exponent of the largest unit of `system` that still fits into `bytes`.
+pub(crate) fn exponent(bytes: u128, system: UnitSystem) -> usize {
+    let base = u128::from(system.base());
+    let mut exp = 0;
+    let mut divisor = base;
+    while exp < UNITS.len() && divisor <= bytes {
+        divisor *= base;
+        exp += 1;
+    }
+    exp
+}
+
+/// Exact decimal expansion of `numerator / divisor`, rounded to a fixed number of places.
+struct Fixed {
+    integer: u128,
+    fraction: [u8; FRACTION_DIGITS],
+    digits: usize,
+}
+
+impl Fixed {
+    fn new(numerator: u128, divisor: u128, precision: usize, rounding: Rounding) -> Fixed {
+        let mut fixed = Fixed {
+            integer: numerator / divisor,
+            fraction: [0; FRACTION_DIGITS],
+            digits: precision.min(FRACTION_DIGITS),
+        };
+
+        // long division, one decimal digit at a time
+        let mut remainder = numerator % divisor;
+        for digit in &mut fixed.fraction[..fixed.digits] {
+            remainder *= 10;
+            *digit = (remainder / divisor) as u8;
+            remainder %= divisor;
+        }
+
+        let round_up = match rounding {
+            Rounding::Floor => false,
+            Rounding::Ceil => remainder != 0,
+            Rounding::HalfUp => remainder * 2 >= divisor,
+            Rounding::HalfEven => match (remainder * 2).cmp(&divisor) {
+                Ordering::Less => false,
+                Ordering::Greater => true,
+                Ordering::Equal => fixed.last_digit() % 2 == 1,
+            },
+        };
+        if round_up {
+            fixed.increment();
+        }
+
+        fixed
+    }
+
+    fn last_digit(&self) -> u8 {
+        match self.digits {
+            0 => (self.integer % 10) as u8,
+            n => self.fraction[n - 1],
+        }
+    }
+
+    /// Adds one unit in the last place, carrying into the integer part if needed.
+    fn increment(&mut self) {
+        for digit in self.fraction[..self.digits].iter_mut().rev() {
+            if *digit < 9 {
+                *digit += 1;
+                return;
+            }
+            *digit = 0;
+        }
+        self.integer += 1;
+    }
+
+    fn write<W: fmt::Write>(&self, w: &mut W, precision: usize) -> fmt::Result {
+        write!(w, "{}", self.integer)?;
+        if precision == 0 {
+            return Ok(());
+        }
+
+        w.write_char('.')?;
+        for digit in &self.fraction[..self.digits] {
+            w.write_char(char::from(b'0' + digit))?;
+        }
+        for _ in self.digits..precision {
+            w.write_char('0')?;
+        }
+        Ok(())
+    }
+}
+
+struct CharCounter(usize);
+
+impl fmt::Write for CharCounter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.chars().count();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ByteSize;
+
+    #[test]
+    fn builder() {
+        let size = ByteSize::mib(3) + ByteSize::kib(512);
+
+        assert_eq!("3.5 MiB", size.display().to_string());
+        assert_eq!("3.50 MiB", size.display().precision(2).to_string());
+        assert_eq!("4 MiB", size.display().precision(0).to_string());
+        assert_eq!("3.5MiB", size.display().space(false).to_string());
+        assert_eq!(
+            "3.5\u{a0}MiB",
+            size.display()
+                .separator(Separator::NonBreakingSpace)
+                .to_string()
+        );
+        assert_eq!("3.5 Mi", size.display().style(UnitStyle::Short).to_string());
+        assert_eq!("3.7 MB", size.display().decimal().to_string());
+        assert_eq!(
+            "3.7M",
+            size.display()
+                .decimal()
+                .style(UnitStyle::Short)
+                .space(false)
+                .to_string()
+        );
+        assert_eq!("3.5 MB", size.display().jedec().to_string());
+    }
+
+    #[test]
+    fn bytes() {
+        assert_eq!("512 B", ByteSize::b(512).display().precision(3).to_string());
+        assert_eq!("512B", ByteSize::b(512).display().space(false).to_string());
+        assert_eq!(
+            "512 B",
+            ByteSize::b(512)
+                .display()
+                .style(UnitStyle::Short)
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn padding() {
+        let size = ByteSize::kib(2);
+
+        assert_eq!("|2.0 KiB   |", format!("|{:10}|", size.display()));
+        assert_eq!("|   2.0 KiB|", format!("|{:>10}|", size.display()));
+        assert_eq!("|-2.0 KiB--|", format!("|{:-^10}|", size.display()));
+        assert_eq!(
+            "|  2.0\u{a0}KiB|",
+            format!(
+                "|{:>9}|",
+                size.display().separator(Separator::NonBreakingSpace)
+            )
+        );
+        assert_eq!("|2.0 KiB|", format!("|{:3}|", size.display()));
+    }
+
+    #[test]
+    fn exact_rounding() {
+        // ties are rounded half to even on the exact value
+        assert_eq!("1.2 KB", ByteSize::b(1250).display().decimal().to_string());
+        assert_eq!("1.4 KB", ByteSize::b(1350).display().decimal().to_string());
+        assert_eq!(
+            "1.25 KiB",
+            ByteSize::b(1280).display().precision(2).to_string()
+        );
+        assert_eq!("1.2 KiB", ByteSize::b(1280).display().to_string());
+        assert_eq!(
+            "2 KiB",
+            ByteSize::b(1536).display().precision(0).to_string()
+        );
+        assert_eq!(
+            "2 KiB",
+            ByteSize::b(2560).display().precision(0).to_string()
+        );
+
+        // unit boundaries are chosen without floating point
+        assert_eq!("1.0 MB", ByteSize::mb(1).display().decimal().to_string());
+        assert_eq!(
+            "999.9 KB",
+            ByteSize::b(999_940).display().decimal().to_string()
+        );
+        assert_eq!("976.6 KiB", ByteSize::mb(1).display().to_string());
+        assert_eq!("1.0 MiB", ByteSize::mib(1).display().to_string());
+    }
+
+    #[test]
+    fn promotion_after_rounding() {
+        let almost_mib = ByteSize::b(1_048_535); // 1023.96 KiB
+
+        assert_eq!("1.0 MiB", almost_mib.display().to_string());
+        assert_eq!("1023.96 KiB", almost_mib.display().precision(2).to_string());
+        assert_eq!(
+            "1 MiB",
+            (ByteSize::kib(1023) + 512u64)
+                .display()
+                .precision(0)
+                .to_string()
+        );
+        assert_eq!(
+            "1023 KiB",
+            ByteSize::kib(1023).display().precision(0).to_string()
+        );
+        assert_eq!(
+            "1.0 MB",
+            ByteSize::b(999_950).display().decimal().to_string()
+        );
+        assert_eq!(
+            "999.9 KB",
+            ByteSize::b(999_949).display().decimal().to_string()
+        );
+        assert_eq!("1.0 GiB", (ByteSize::gib(1) - 1u64).display().to_string());
+        assert_eq!("16.0 EiB", ByteSize(u64::MAX).display().to_string());
+    }
+
+    #[test]
+    fn rounding_modes() {
+        let size = ByteSize::b(1_126); // 1.099609375 KiB
+        let display = size.display().precision(2);
+
+        assert_eq!("1.09 KiB", display.rounding(Rounding::Floor).to_string());
+        assert_eq!("1.10 KiB", display.rounding(Rounding::Ceil).to_string());
+        assert_eq!("1.10 KiB", display.rounding(Rounding::HalfUp).to_string());
+        assert_eq!("1.10 KiB", display.rounding(Rounding::HalfEven).to_string());
+
+        // exact ties
+        let tie = ByteSize::b(1_250).display().decimal();
+        assert_eq!("1.2 KB", tie.rounding(Rounding::Floor).to_string());
+        assert_eq!("1.3 KB", tie.rounding(Rounding::Ceil).to_string());
+        assert_eq!("1.3 KB", tie.rounding(Rounding::HalfUp).to_string());
+        assert_eq!("1.2 KB", tie.rounding(Rounding::HalfEven).to_string());
+
+        // exact values are never moved
+        let exact = ByteSize::kib(3).display();
+        assert_eq!("3.0 KiB", exact.rounding(Rounding::Floor).to_string());
+        assert_eq!("3.0 KiB", exact.rounding(Rounding::Ceil).to_string());
+
+        // floor never promotes, ceil promotes as soon as the value exceeds the boundary
+        let almost_mib = ByteSize::mib(1) - 1u64;
+        assert_eq!(
+            "1023.9 KiB",
+            almost_mib.display().rounding(Rounding::Floor).to_string()
+        );
+        assert_eq!(
+            "1.0 MiB",
+            almost_mib.display().rounding(Rounding::Ceil).to_string()
+        );
+        assert_eq!(
+            "1.1 MiB",
+            (ByteSize::mib(1) + 1u64)
+                .display()
+                .rounding(Rounding::Ceil)
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn significant_digits() {
+        let sig = |size: ByteSize, digits| size.display().significant(digits).to_string();
+
+        assert_eq!("1.5 KiB", sig(ByteSize::b(1536), 2));
+        assert_eq!("1.50 KiB", sig(ByteSize::b(1536), 3));
+        assert_eq!("23 KiB", sig(ByteSize::kib(23), 2));
+        assert_eq!("23.0 KiB", sig(ByteSize::kib(23), 3));
+        assert_eq!("512 MiB", sig(ByteSize::mib(512), 2));
+        assert_eq!("1000 KiB", sig(ByteSize::kib(1000), 2));
+        assert_eq!("817 B", sig(ByteSize::b(817), 2));
+
+        // rounding into an extra integer digit drops a decimal place
+        assert_eq!("10 KiB", sig(ByteSize::b(10_220), 2));
+        assert_eq!("9.9 KiB", sig(ByteSize::b(10_137), 2));
+
+        // a format string precision switches back to fixed decimal places
+        assert_eq!(
+            "23.00 KiB",
+            format!("{:.2}", ByteSize::kib(23).display().significant(2))
+        );
+    }
+
+    #[test]
+    fn coreutils_style() {
+        let ls = |size: ByteSize| {
+            size.display()
+                .significant(2)
+                .rounding(Rounding::Ceil)
+                .style(UnitStyle::Terse)
+                .space(false)
+                .to_string()
+        };
+
+        assert_eq!("0", ls(ByteSize::b(0)));
+        assert_eq!("1023", ls(ByteSize::b(1023)));
+        assert_eq!("1.0K", ls(ByteSize::kib(1)));
+        assert_eq!("1.1K", ls(ByteSize::b(1025)));
+        assert_eq!("4.0K", ls(ByteSize::kib(4)));
+        assert_eq!("10K", ls(ByteSize::b(10_239)));
+        assert_eq!("1.0M", ls(ByteSize::b(1_048_064)));
+        assert_eq!("2.0G", ls(ByteSize::gib(2)));
+
+        assert_eq!(
+            "1.5k",
+            ByteSize::b(1500)
+                .display()
+                .decimal()
+                .style(UnitStyle::Terse)
+                .space(false)
+                .to_string()
+        );
+        assert_eq!(
+            "1.5 M",
+            ByteSize::kib(1536)
+                .display()
+                .style(UnitStyle::Terse)
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn fixed_unit() {
+        let gib = |size: ByteSize| size.display().unit(Unit::GiB).to_string();
+
+        assert_eq!("0.5 GiB", gib(ByteSize::mib(512)));
+        assert_eq!("12.0 GiB", gib(ByteSize::gib(12)));
+        assert_eq!("1024.0 GiB", gib(ByteSize::tib(1)));
+        assert_eq!("0.0 GiB", gib(ByteSize::b(1)));
+
+        // the fixed unit wins over the unit system
+        assert_eq!(
+            "1.50 MB",
+            ByteSize::kb(1500)
+                .display()
+                .binary()
+                .unit(Unit::MB)
+                .precision(2)
+                .to_string()
+        );
+        assert_eq!(
+            "1.5 Gi",
+            ByteSize::mib(1536)
+                .display()
+                .unit(Unit::GiB)
+                .style(UnitStyle::Short)
+                .to_string()
+        );
+        assert_eq!(
+            "1024 B",
+            ByteSize::kib(1).display().unit(Unit::B).to_string()
+        );
+    }
+
+    #[test]
+    fn long_names() {
+        let long = |size: ByteSize| size.display().style(UnitStyle::Long).to_string();
+
+        assert_eq!("0 bytes", long(ByteSize::b(0)));
+        assert_eq!("1 byte", long(ByteSize::b(1)));
+        assert_eq!("2 bytes", long(ByteSize::b(2)));
+        assert_eq!("1.5 mebibytes", long(ByteSize::kib(1536)));
+        assert_eq!("1.0 kibibytes", long(ByteSize::kib(1)));
+        assert_eq!(
+            "1 kibibyte",
+            ByteSize::kib(1)
+                .display()
+                .style(UnitStyle::Long)
+                .precision(0)
+                .to_string()
+        );
+        assert_eq!(
+            "2 kilobytes",
+            ByteSize::kb(2)
+                .display()
+                .decimal()
+                .style(UnitStyle::Long)
+                .precision(0)
+                .to_string()
+        );
+        assert_eq!(
+            "1.5 gigabytes",
+            ByteSize::mib(1536)
+                .display()
+                .jedec()
+                .style(UnitStyle::Long)
+                .to_string()
+        );
+        assert_eq!(
+            "1.5 gibibytes",
+            ByteSize::mib(1536)
+                .display()
+                .unit(Unit::GiB)
+                .style(UnitStyle::Long)
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn bits() {
+        let bits = |size: ByteSize| size.display().bits().to_string();
+
+        assert_eq!("0 bit", bits(ByteSize::b(0)));
+        assert_eq!("8 bit", bits(ByteSize::b(1)));
+        assert_eq!("1016 bit", bits(ByteSize::b(127)));
+        assert_eq!("1.0 Kibit", bits(ByteSize::b(128)));
+        assert_eq!("8.0 Kibit", bits(ByteSize::kib(1)));
+        assert_eq!("8.0 Mibit", bits(ByteSize::mib(1)));
+        assert_eq!("1.0 Gibit", bits(ByteSize::mib(128)));
+        assert_eq!("8.0 Eibit", bits(ByteSize::eib(1)));
+
+        let decimal = |size: ByteSize| size.display().decimal().bits().to_string();
+        assert_eq!("1.0 kbit", decimal(ByteSize::b(125)));
+        assert_eq!("1.5 Mbit", decimal(ByteSize::b(187_500)));
+        assert_eq!("1.0 Gbit", decimal(ByteSize::mb(125)));
+        assert_eq!(
+            "1.0 Kbit",
+            ByteSize::b(128).display().jedec().bits().to_string()
+        );
+
+        let size = ByteSize::mb(125).display().decimal().bits();
+        assert_eq!("1.0 Gb", size.style(UnitStyle::Short).to_string());
+        assert_eq!("1.0 gigabits", size.style(UnitStyle::Long).to_string());
+        assert_eq!("1000 Mbit", size.unit(Unit::MB).precision(0).to_string());
+        assert_eq!(
+            "2.0 Mib",
+            ByteSize::kib(256)
+                .display()
+                .bits()
+                .style(UnitStyle::Short)
+                .to_string()
+        );
+        assert_eq!(
+            "8 bits",
+            ByteSize::b(1)
+                .display()
+                .bits()
+                .style(UnitStyle::Long)
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn breakdown() {
+        let all = |size: ByteSize| size.display().breakdown(None).to_string();
+
+        assert_eq!("0 B", all(ByteSize::b(0)));
+        assert_eq!("1 B", all(ByteSize::b(1)));
+        assert_eq!("1 KiB", all(ByteSize::kib(1)));
+        assert_eq!("1 KiB 1 B", all(ByteSize::b(1025)));
+        assert_eq!("1 MiB 1 B", all(ByteSize::mib(1) + ByteSize::b(1)));
+        assert_eq!(
+            "15 EiB 1023 PiB 1023 TiB 1023 GiB 1023 MiB 1023 KiB 1023 B",
+            all(ByteSize(u64::MAX))
+        );
+
+        let size = ByteSize::gib(1) + ByteSize::mib(512) + ByteSize::kib(3);
+        assert_eq!("1 GiB", size.display().breakdown(Some(1)).to_string());
+        assert_eq!("1 GiB", size.display().breakdown(Some(0)).to_string());
+        assert_eq!(
+            "1 GiB 512 MiB 3 KiB",
+            size.display().breakdown(Some(5)).to_string()
+        );
+        assert_eq!(
+            "1GiB512MiB3KiB",
+            size.display().breakdown(None).space(false).to_string()
+        );
+        assert_eq!(
+            "1Gi512Mi3Ki",
+            size.display()
+                .breakdown(None)
+                .style(UnitStyle::Terse)
+                .space(false)
+                .to_string()
+        );
+        assert_eq!(
+            "1 gibibyte 512 mebibytes 3 kibibytes",
+            size.display()
+                .breakdown(None)
+                .style(UnitStyle::Long)
+                .to_string()
+        );
+        assert_eq!(
+            "1 MB 500 KB 7 B",
+            ByteSize::b(1_500_007)
+                .display()
+                .decimal()
+                .breakdown(None)
+                .to_string()
+        );
+        assert_eq!(
+            "|1 KiB 1 B  |",
+            format!("|{:<11}|", ByteSize::b(1025).display().breakdown(None))
+        );
+
+        // bit counts past the range of a `u128` are still split exactly
+        assert_eq!(
+            "2147483647 Qibit 1023 Ribit 1023 Yibit 1023 Zibit 1023 Eibit 1023 Pibit \
+             1023 Tibit 1023 Gibit 1023 Mibit 1023 Kibit 1016 bit",
+            crate::ByteSize128(u128::MAX)
+                .display()
+                .bits()
+                .breakdown(None)
+                .to_string()
+        );
+        assert_eq!(
+            "12 Kibit 8 bit",
+            ByteSize::b(1537)
+                .display()
+                .bits()
+                .breakdown(None)
+                .to_string()
+        );
+
+        // units that would parse back as something else are written unambiguously
+        let terse = size.display().breakdown(None).style(UnitStyle::Terse);
+        assert_eq!("1 Gi 512 Mi 3 Ki", terse.to_string());
+        assert_eq!(size, terse.to_string().parse::<ByteSize>().unwrap());
+        assert_eq!("1 Gi 512 Mi 3 Ki", terse.jedec().to_string());
+        assert_eq!("1 G 610 M 615 k 808", terse.decimal().to_string());
+        assert_eq!("12 Gib 24 Kib", terse.bits().to_string());
+        assert_eq!(
+            "1 gibibyte 512 mebibytes 3 kibibytes",
+            size.display()
+                .jedec()
+                .breakdown(None)
+                .style(UnitStyle::Long)
+                .to_string()
+        );
+        assert_eq!(
+            "1\u{a0}GiB\u{a0}512\u{a0}MiB\u{a0}3\u{a0}KiB",
+            size.display()
+                .breakdown(None)
+                .separator(Separator::NonBreakingSpace)
+                .to_string()
+        );
+
+        // the unlimited breakdown is exact and parses back
+        let styles = [
+            UnitStyle::Symbol,
+            UnitStyle::Short,
+            UnitStyle::Terse,
+            UnitStyle::Long,
+        ];
+        let systems = [UnitSystem::Binary, UnitSystem::Decimal, UnitSystem::Jedec];
+        let separators = [
+            Separator::Space,
+            Separator::None,
+            Separator::NonBreakingSpace,
+        ];
+        for bytes in [0, 1, 1025, 1_500_007, 123_456_789_012, u64::MAX] {
+            let size = ByteSize(bytes);
+            assert_eq!(size, all(size).parse::<ByteSize>().unwrap());
+            for (style, system, separator) in styles
+                .iter()
+                .flat_map(|&style| systems.iter().map(move |&system| (style, system)))
+                .flat_map(|(style, system)| separators.iter().map(move |&s| (style, system, s)))
+            {
+                let display = size
+                    .display()
+                    .system(system)
+                    .style(style)
+                    .separator(separator)
+                    .breakdown(None);
+                let options = crate::ParseOptions::new().system(system);
+                for output in [display.to_string(), display.bits().to_string()] {
+                    assert_eq!(size, options.parse(&output).unwrap().size, "{}", output);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn large_values() {
+        assert_eq!(
+            "15.999999999999999999132638262011596452794037759304046630859375 EiB",
+            ByteSize(u64::MAX).display().precision(60).to_string()
+        );
+        assert_eq!(
+            "18.446744073709551615000 EB",
+            ByteSize(u64::MAX)
+                .display()
+                .decimal()
+                .precision(21)
+                .to_string()
+        );
+        assert_eq!(
+            "1.0000000000000000000000000000000000000000000000000000000000000000000000 EiB",
+            ByteSize::b(1 << 60).display().precision(70).to_string()
+        );
+    }
+
+    #[test]
+    fn format_flags() {
+        let size = ByteSize::kib(2);
+
+        assert_eq!("2.000 KiB", format!("{:.3}", size.display().precision(1)));
+        assert_eq!("+2.0 KiB", format!("{:+}", size.display()));
+        assert_eq!("|  +2.00 KiB|", format!("|{:>+11.2}|", size.display()));
+    }
+}
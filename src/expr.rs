@@ -0,0 +1,539 @@
+use std::ops::Range;
+
+use super::{ByteSize, ByteSize128, ParseError, ParseErrorKind, ParseOptions};
+use crate::parse::{digits_value, number_len, radix_literal};
+
+/// An exact intermediate result: `numerator / denominator`, either a size in bytes or a
+/// plain scalar.
+#[derive(Debug, Copy, Clone)]
+struct Value {
+    numerator: u128,
+    denominator: u128,
+    /// Whether the value is below zero, which only the final result must not be.
+    negative: bool,
+    size: bool,
+}
+
+/// Deepest nesting of parentheses accepted, so that hostile input cannot overflow the stack.
+const MAX_DEPTH: usize = 128;
+
+/// Recursive descent evaluator over `+ - * /`, parentheses and size or scalar literals.
+struct Evaluator<'a> {
+    options: &'a ParseOptions,
+    input: &'a str,
+    offset: usize,
+    /// Parentheses currently open.
+    depth: usize,
+}
+
+impl ParseOptions {
+    /// Evaluates an arithmetic expression of sizes into a [`ByteSize`].
+    ///
+    /// Literals are sizes (`2 GiB`) or plain scalars (`3`), combined with `+`, `-`, `*`, `/`
+    /// and parentheses. Sizes can be multiplied or divided by scalars, and divided by
+    /// sizes to give a scalar. A scalar added to a size, or left as the result, counts in
+    /// the [`default_unit()`](ParseOptions::default_unit). Intermediate results are exact
+    /// fractions and may be negative; only the final result must not be, and is rounded
+    /// down to whole bytes, or rejected under [`exact()`](ParseOptions::exact). Parentheses
+    /// nest at most 128 deep.
+    ///
+    /// ```
+    /// use bytesize::{ByteSize, ParseErrorKind, ParseOptions};
+    ///
+    /// let options = ParseOptions::new();
+    /// assert_eq!(options.eval("(2 GiB + 512 MiB) * 3 / 4"), Ok(ByteSize::mib(1920)));
+    /// assert_eq!(options.eval("0.1 KiB * 10"), Ok(ByteSize::kib(1)));
+    /// assert_eq!(
+    ///     options.eval("1 GiB / (2 - 2)").unwrap_err().kind(),
+    ///     ParseErrorKind::DivideByZero
+    /// );
+    /// ```
+    pub fn eval(&self, input: &str) -> Result<ByteSize, ParseError> {
+        let bytes = self.eval128(input)?.0;
+        u64::try_from(bytes)
+            .map(ByteSize)
+            .map_err(|_| ParseError::new(ParseErrorKind::Overflow, input, 0..input.len()))
+    }
+
+    /// Evaluates an arithmetic expression of sizes into a [`ByteSize128`], like
+    /// [`eval()`](ParseOptions::eval).
+    pub fn eval128(&self, input: &str) -> Result<ByteSize128, ParseError> {
+        if input.trim().is_empty() {
+            return Err(ParseError::new(
+                ParseErrorKind::Empty,
+                input,
+                0..input.len(),
+            ));
+        }
+        let mut evaluator = Evaluator {
+            options: self,
+            input,
+            offset: 0,
+            depth: 0,
+        };
+        let start = evaluator.skip_whitespace();
+        let value = evaluator.expression()?;
+        let end = evaluator.skip_whitespace();
+        if end != input.len() {
+            return Err(evaluator.unexpected());
+        }
+
+        let span = start..input.len();
+        let value = evaluator.as_size(value, span.clone())?;
+        if value.negative {
+            return Err(ParseError::new(ParseErrorKind::Negative, input, span));
+        }
+        if self.exact && value.numerator % value.denominator != 0 {
+            return Err(ParseError::new(
+                ParseErrorKind::FractionalBytes,
+                input,
+                span,
+            ));
+        }
+        Ok(ByteSize128(value.numerator / value.denominator))
+    }
+}
+
+impl ByteSize {
+    /// Evaluates an arithmetic expression of sizes such as `"(2 GiB + 512 MiB) * 3 / 4"`
+    /// with the default [`ParseOptions`].
+    pub fn eval(input: &str) -> Result<ByteSize, ParseError> {
+        ParseOptions::new().eval(input)
+    }
+}
+
+impl ByteSize128 {
+    /// Evaluates an arithmetic expression of sizes such as `"(2 ZiB + 512 EiB) * 3 / 4"`
+    /// with the default [`ParseOptions`].
+    pub fn eval(input: &str) -> Result<ByteSize128, ParseError> {
+        ParseOptions::new().eval128(input)
+    }
+}
+
+impl<'a> Evaluator<'a> {
+    /// expression := term (('+' | '-') term)*
+    fn expression(&mut self) -> Result<Value, ParseError> {
+        let start = self.offset;
+        let mut value = self.term()?;
+        loop {
+            self.skip_whitespace();
+            let add = match self.peek() {
+                Some('+') => true,
+                Some('-') => false,
+                _ => return Ok(value),
+            };
+            self.offset += 1;
+            let rhs = self.term()?;
+            let span = start..self.offset;
+            let (lhs, rhs) = if value.size == rhs.size {
+                (value, rhs)
+            } else {
+                (
+                    self.as_size(value, span.clone())?,
+                    self.as_size(rhs, span.clone())?,
+                )
+            };
+            value = if add { lhs.add(rhs) } else { lhs.sub(rhs) }
+                .ok_or_else(|| ParseError::new(ParseErrorKind::Overflow, self.input, span))?;
+        }
+    }
+
+    /// term := factor (('*' | '/') factor)*
+    fn term(&mut self) -> Result<Value, ParseError> {
+        let start = self.offset;
+        let mut value = self.factor()?;
+        loop {
+            self.skip_whitespace();
+            let operator = self.offset;
+            let multiply = match self.peek() {
+                Some('*') => true,
+                Some('/') => false,
+                _ => return Ok(value),
+            };
+            self.offset += 1;
+            let rhs = self.factor()?;
+            let span = start..self.offset;
+            value = if multiply {
+                if value.size && rhs.size {
+                    // bytes squared are not a size
+                    return Err(ParseError::new(
+                        ParseErrorKind::InvalidExpression,
+                        self.input,
+                        operator..operator + 1,
+                    ));
+                }
+                value.mul(rhs, value.size || rhs.size)
+            } else {
+                if rhs.numerator == 0 {
+                    return Err(ParseError::new(
+                        ParseErrorKind::DivideByZero,
+                        self.input,
+                        span,
+                    ));
+                }
+                if rhs.size && !value.size {
+                    // a scalar per byte is not a size either
+                    return Err(ParseError::new(
+                        ParseErrorKind::InvalidExpression,
+                        self.input,
+                        operator..operator + 1,
+                    ));
+                }
+                value.mul(rhs.recip(), value.size && !rhs.size)
+            }
+            .ok_or_else(|| ParseError::new(ParseErrorKind::Overflow, self.input, span))?;
+        }
+    }
+
+    /// factor := '(' expression ')' | literal
+    fn factor(&mut self) -> Result<Value, ParseError> {
+        self.skip_whitespace();
+        if self.peek() == Some('(') {
+            if self.depth == MAX_DEPTH {
+                return Err(self.unexpected());
+            }
+            self.depth += 1;
+            self.offset += 1;
+            let value = self.expression()?;
+            self.skip_whitespace();
+            if self.peek() != Some(')') {
+                return Err(self.unexpected());
+            }
+            self.offset += 1;
+            self.depth -= 1;
+            return Ok(value);
+        }
+        self.literal()
+    }
+
+    /// literal := number unit?
+    fn literal(&mut self) -> Result<Value, ParseError> {
+        let start = self.offset;
+        let rest = &self.input[start..];
+        let len = number_len(rest, self.options.separators);
+        if len == 0 {
+            return Err(self.unexpected());
+        }
+        let number = &rest[..len];
+        let valid = match radix_literal(number) {
+            Some((radix, digits)) => digits.chars().all(|c| c == '_' || c.is_digit(radix)),
+            None => self.options.is_decimal(number),
+        };
+        if !valid {
+            return Err(ParseError::new(
+                ParseErrorKind::InvalidNumber,
+                self.input,
+                start..start + len,
+            ));
+        }
+        self.offset += len;
+
+        let span = start..self.offset;
+        let overflow = || ParseError::new(ParseErrorKind::Overflow, self.input, span.clone());
+        let (numerator, denominator) =
+            number_value(number, self.options.separators).ok_or_else(overflow)?;
+        let number = Value {
+            numerator,
+            denominator,
+            negative: false,
+            size: false,
+        };
+
+        // a unit may follow after whitespace, but is only letters
+        let after = self.offset + self.input[self.offset..].len()
+            - self.input[self.offset..].trim_start().len();
+        let unit_len = self.input[after..]
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(self.input.len() - after);
+        if unit_len == 0 {
+            return Ok(number);
+        }
+        let (unit, bits, _) = self.options.unit(self.input, after..after + unit_len)?;
+        self.offset = after + unit_len;
+
+        let span = start..self.offset;
+        let factor = Value {
+            numerator: unit.factor(),
+            denominator: if bits { 8 } else { 1 },
+            negative: false,
+            size: true,
+        };
+        number
+            .mul(factor, true)
+            .ok_or_else(|| ParseError::new(ParseErrorKind::Overflow, self.input, span))
+    }
+
+    /// Reads a scalar as a count of the default unit.
+    fn as_size(&self, value: Value, span: Range<usize>) -> Result<Value, ParseError> {
+        if value.size {
+            return Ok(value);
+        }
+        let unit = Value {
+            numerator: self.options.default_unit.factor(),
+            denominator: 1,
+            negative: false,
+            size: true,
+        };
+        value
+            .mul(unit, true)
+            .ok_or_else(|| ParseError::new(ParseErrorKind::Overflow, self.input, span))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.offset..].chars().next()
+    }
+
+    /// Skips whitespace, returning the new offset.
+    fn skip_whitespace(&mut self) -> usize {
+        let rest = &self.input[self.offset..];
+        self.offset += rest.len() - rest.trim_start().len();
+        self.offset
+    }
+
+    /// Error for the token at the current offset, or for the end of the input.
+    fn unexpected(&self) -> ParseError {
+        let len = self.peek().map_or(0, char::len_utf8);
+        ParseError::new(
+            ParseErrorKind::InvalidExpression,
+            self.input,
+            self.offset..self.offset + len,
+        )
+    }
+}
+
+impl Value {
+    fn add(self, rhs: Value) -> Option<Value> {
+        let gcd = gcd(self.denominator, rhs.denominator);
+        let lhs_numerator = self.numerator.checked_mul(rhs.denominator / gcd)?;
+        let rhs_numerator = rhs.numerator.checked_mul(self.denominator / gcd)?;
+        let (numerator, negative) = if self.negative == rhs.negative {
+            (lhs_numerator.checked_add(rhs_numerator)?, self.negative)
+        } else if lhs_numerator >= rhs_numerator {
+            (lhs_numerator - rhs_numerator, self.negative)
+        } else {
+            (rhs_numerator - lhs_numerator, rhs.negative)
+        };
+        let denominator = (self.denominator / gcd).checked_mul(rhs.denominator)?;
+        Some(Value::reduced(numerator, denominator, negative, self.size))
+    }
+
+    fn sub(self, rhs: Value) -> Option<Value> {
+        self.add(Value {
+            negative: !rhs.negative,
+            ..rhs
+        })
+    }
+
+    fn mul(self, rhs: Value, size: bool) -> Option<Value> {
+        // cancel crosswise first to keep the products small
+        let a = gcd(self.numerator, rhs.denominator);
+        let b = gcd(rhs.numerator, self.denominator);
+        let numerator = (self.numerator / a).checked_mul(rhs.numerator / b)?;
+        let denominator = (self.denominator / b).checked_mul(rhs.denominator / a)?;
+        let negative = self.negative != rhs.negative;
+        Some(Value::reduced(numerator, denominator, negative, size))
+    }
+
+    fn recip(self) -> Value {
+        Value {
+            numerator: self.denominator,
+            denominator: self.numerator,
+            ..self
+        }
+    }
+
+    fn reduced(numerator: u128, denominator: u128, negative: bool, size: bool) -> Value {
+        let gcd = gcd(numerator, denominator);
+        Value {
+            numerator: numerator / gcd,
+            denominator: denominator / gcd,
+            // zero has no sign
+            negative: negative && numerator != 0,
+            size,
+        }
+    }
+}
+
+/// Exact value of a decimal or radix literal as a fraction, or `None` on overflow.
+fn number_value(number: &str, separators: &[char]) -> Option<(u128, u128)> {
+    if let Some((radix, digits)) = radix_literal(number) {
+        let value =
+            digits
+                .chars()
+                .filter_map(|c| c.to_digit(radix))
+                .try_fold(0u128, |value, digit| {
+                    value
+                        .checked_mul(u128::from(radix))?
+                        .checked_add(u128::from(digit))
+                })?;
+        return Some((value, 1));
+    }
+
+    let (mantissa, exponent) = match number.find(['e', 'E']) {
+        Some(i) => (&number[..i], &number[i + 1..]),
+        None => (number, ""),
+    };
+    let places = mantissa
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len());
+    let mut exponent = match exponent.strip_prefix('-') {
+        Some(digits) => -digits_value(digits),
+        None => digits_value(exponent.trim_start_matches('+')),
+    }
+    .saturating_sub(places as i64);
+
+    let mut numerator: u128 = 0;
+    for c in mantissa
+        .chars()
+        .filter(|c| !separators.contains(c) && *c != '.')
+    {
+        numerator = numerator
+            .checked_mul(10)?
+            .checked_add(u128::from(c.to_digit(10)?))?;
+    }
+    // 10^38 is the largest power of ten in a `u128`; places beyond it are dropped, which
+    // changes the value by less than a hundred-millionth of a byte even in quebibytes
+    while exponent < -38 && numerator != 0 {
+        numerator /= 10;
+        exponent += 1;
+    }
+    if numerator == 0 {
+        return Some((0, 1));
+    }
+    let scale = 10u128.checked_pow(u32::try_from(exponent.unsigned_abs()).ok()?)?;
+    if exponent < 0 {
+        Some((numerator, scale))
+    } else {
+        Some((numerator.checked_mul(scale)?, 1))
+    }
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Unit;
+
+    #[test]
+    fn eval() {
+        // shortcut for writing test cases
+        fn eval(s: &str) -> u64 {
+            ByteSize::eval(s).unwrap().0
+        }
+
+        assert_eq!(eval("(2 GiB + 512 MiB) * 3 / 4"), 1920 << 20);
+        assert_eq!(eval("2 GiB + 512 MiB * 3 / 4"), (2 << 30) + (384 << 20));
+        assert_eq!(eval("1 GiB - 1 MiB"), (1 << 30) - (1 << 20));
+        assert_eq!(eval("10 - 2 - 3"), 5);
+        assert_eq!(eval("2 * (3 KiB + 4 KiB)"), 14 << 10);
+        assert_eq!(eval("4096"), 4096);
+        assert_eq!(eval("1 KiB + 1"), 1025);
+        assert_eq!(eval("1.5 * 2 GiB"), 3 << 30);
+        assert_eq!(eval("0.1 KiB * 10"), 1024);
+        assert_eq!(eval("1 KiB / 3 * 3"), 1024);
+        assert_eq!(eval("1 KiB / 3"), 341);
+        assert_eq!(eval("8 GiB / 2 GiB * 1 MiB"), 4 << 20);
+        assert_eq!(eval("1e3 * 4 KB"), 4_000_000);
+        assert_eq!(eval("1 GiB - 2 GiB + 2 GiB"), 1 << 30);
+        assert_eq!(eval("(1 - 3) * (1 KiB - 2 KiB)"), 2048);
+        assert_eq!(eval("1e-39 KiB"), 0);
+        assert_eq!(eval("0.00000000000000000000000000000000000000001 KiB"), 0);
+        assert_eq!(eval("0x10 * 4 KiB"), 64 << 10);
+        assert_eq!(eval("1,000 * 8 bit"), 1000);
+        assert_eq!(eval("  ( ( 1 MiB ) )  "), 1 << 20);
+        assert_eq!(eval("16 EiB - 1"), u64::MAX);
+        assert_eq!(
+            ByteSize128::eval("(2 ZiB + 512 EiB) * 3 / 4").unwrap().0,
+            1920 * Unit::EiB.factor()
+        );
+        assert_eq!(
+            ParseOptions::new()
+                .default_unit(Unit::MiB)
+                .eval("1 GiB + 512")
+                .unwrap(),
+            ByteSize::mib(1536)
+        );
+    }
+
+    #[test]
+    fn eval_errors() {
+        // shortcut for writing test cases
+        fn error(s: &str) -> (ParseErrorKind, Range<usize>) {
+            let error = ByteSize::eval(s).unwrap_err();
+            (error.kind(), error.span())
+        }
+
+        assert_eq!(error(""), (ParseErrorKind::Empty, 0..0));
+        assert_eq!(error("1 GiB / 0"), (ParseErrorKind::DivideByZero, 0..9));
+        assert_eq!(
+            error("1 GiB / (1 - 1)"),
+            (ParseErrorKind::DivideByZero, 0..15)
+        );
+        assert_eq!(error("16 EiB"), (ParseErrorKind::Overflow, 0..6));
+        assert_eq!(error("1 MiB - 1 GiB"), (ParseErrorKind::Negative, 0..13));
+        assert_eq!(error("1 - 2 * 1"), (ParseErrorKind::Negative, 0..9));
+        assert_eq!(
+            error("(1 GiB - 2 GiB) / 2"),
+            (ParseErrorKind::Negative, 0..19)
+        );
+        assert_eq!(
+            ByteSize128::eval("1 QiB * 1 QiB / 1 QiB")
+                .unwrap_err()
+                .kind(),
+            ParseErrorKind::InvalidExpression
+        );
+        assert_eq!(
+            ByteSize128::eval("1e30 QiB").unwrap_err().kind(),
+            ParseErrorKind::Overflow
+        );
+        assert_eq!(
+            error("2 GiB * 2 GiB"),
+            (ParseErrorKind::InvalidExpression, 6..7)
+        );
+        assert_eq!(
+            error("2 / 2 GiB"),
+            (ParseErrorKind::InvalidExpression, 2..3)
+        );
+        assert_eq!(error("2 GiB +"), (ParseErrorKind::InvalidExpression, 7..7));
+        assert_eq!(error("(2 GiB"), (ParseErrorKind::InvalidExpression, 6..6));
+        assert_eq!(error("2 GiB)"), (ParseErrorKind::InvalidExpression, 5..6));
+        assert_eq!(error("2 GiB 3"), (ParseErrorKind::InvalidExpression, 6..7));
+
+        // nesting is limited instead of overflowing the stack
+        let nested = |depth| format!("{}1 KiB{}", "(".repeat(depth), ")".repeat(depth));
+        assert_eq!(ByteSize::eval(&nested(128)), Ok(ByteSize::kib(1)));
+        assert_eq!(
+            error(&nested(129)),
+            (ParseErrorKind::InvalidExpression, 128..129)
+        );
+        assert_eq!(
+            error(&nested(100_000)),
+            (ParseErrorKind::InvalidExpression, 128..129)
+        );
+        assert_eq!(error("1.2.3 KiB"), (ParseErrorKind::InvalidNumber, 0..5));
+        assert_eq!(error("2 GiBB"), (ParseErrorKind::UnknownUnit, 2..6));
+        assert_eq!(
+            ParseOptions::new()
+                .exact(true)
+                .eval("1 KiB / 3")
+                .unwrap_err()
+                .kind(),
+            ParseErrorKind::FractionalBytes
+        );
+
+        assert_eq!(
+            ByteSize::eval("2 GiB +").unwrap_err().to_string(),
+            "unexpected end of expression"
+        );
+        assert_eq!(
+            ByteSize::eval("2 GiB 3").unwrap_err().to_string(),
+            "unexpected \"3\" in expression"
+        );
+    }
+}
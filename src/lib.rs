@@ -21,13 +21,27 @@
 //! It also provides its human readable string as follows:
 //!
 //! ```
-//! use bytesize::ByteSize;
+//! use bytesize::{ByteSize, UnitSystem};
 //!
-//! assert_eq!("482.4 GiB", ByteSize::gb(518).to_string_as(true));
-//! assert_eq!("518.0 GB", ByteSize::gb(518).to_string_as(false));
+//! assert_eq!("482.4 GiB", ByteSize::gb(518).to_string_with(UnitSystem::Binary));
+//! assert_eq!("518.0 GB", ByteSize::gb(518).to_string_with(UnitSystem::Decimal));
 //! ```
 
+#[macro_use]
+mod arithmetic;
+mod bytesize128;
+mod delta;
+mod display;
+mod expr;
+mod literal;
 mod parse;
+mod unit;
+
+pub use crate::bytesize128::ByteSize128;
+pub use crate::delta::ByteSizeDelta;
+pub use crate::display::{Display, Rounding, Separator, UnitStyle};
+pub use crate::parse::{ParseError, ParseErrorKind, ParseOptions, Parsed};
+pub use crate::unit::{Unit, UnitBasis};
 
 #[cfg(feature = "arbitrary")]
 extern crate arbitrary;
@@ -38,8 +52,8 @@ use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 #[cfg(feature = "serde")]
 use std::convert::TryFrom;
 
-use std::fmt::{self, Debug, Display, Formatter};
-use std::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
+use std::fmt::{self, Debug, Formatter};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign};
 
 /// byte size for 1 byte
 pub const B: u64 = 1;
@@ -53,6 +67,8 @@ pub const GB: u64 = 1_000_000_000;
 pub const TB: u64 = 1_000_000_000_000;
 /// bytes size for 1 petabyte
 pub const PB: u64 = 1_000_000_000_000_000;
+/// bytes size for 1 exabyte
+pub const EB: u64 = 1_000_000_000_000_000_000;
 
 /// bytes size for 1 kibibyte
 pub const KIB: u64 = 1_024;
@@ -64,11 +80,26 @@ pub const GIB: u64 = 1_073_741_824;
 pub const TIB: u64 = 1_099_511_627_776;
 /// bytes size for 1 pebibyte
 pub const PIB: u64 = 1_125_899_906_842_624;
-
-static UNITS: &str = "KMGTPE";
-static UNITS_SI: &str = "KMGTPE";
-static LN_KB: f64 = 6.931471806; // ln 1024
-static LN_KIB: f64 = 6.907755279; // ln 1000
+/// bytes size for 1 exbibyte
+pub const EIB: u64 = 1_152_921_504_606_846_976;
+
+/// bytes size for 1 zettabyte
+pub const ZB: u128 = 1_000_000_000_000_000_000_000;
+/// bytes size for 1 yottabyte
+pub const YB: u128 = 1_000_000_000_000_000_000_000_000;
+/// bytes size for 1 ronnabyte
+pub const RB: u128 = 1_000_000_000_000_000_000_000_000_000;
+/// bytes size for 1 quettabyte
+pub const QB: u128 = 1_000_000_000_000_000_000_000_000_000_000;
+
+/// bytes size for 1 zebibyte
+pub const ZIB: u128 = 1_180_591_620_717_411_303_424;
+/// bytes size for 1 yobibyte
+pub const YIB: u128 = 1_208_925_819_614_629_174_706_176;
+/// bytes size for 1 robibyte
+pub const RIB: u128 = 1_237_940_039_285_380_274_899_124_224;
+/// bytes size for 1 quebibyte
+pub const QIB: u128 = 1_267_650_600_228_229_401_496_703_205_376;
 
 pub fn kb<V: Into<u64>>(size: V) -> u64 {
     size.into() * KB
@@ -110,6 +141,14 @@ pub fn pib<V: Into<u64>>(size: V) -> u64 {
     size.into() * PIB
 }
 
+pub fn eb<V: Into<u64>>(size: V) -> u64 {
+    size.into() * EB
+}
+
+pub fn eib<V: Into<u64>>(size: V) -> u64 {
+    size.into() * EIB
+}
+
 /// Byte size representation
 #[derive(Copy, Clone, PartialEq, PartialOrd, Eq, Ord, Hash, Default)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
@@ -171,176 +210,214 @@ impl ByteSize {
         ByteSize(size * PIB)
     }
 
+    #[inline(always)]
+    pub const fn eb(size: u64) -> ByteSize {
+        ByteSize(size * EB)
+    }
+
+    #[inline(always)]
+    pub const fn eib(size: u64) -> ByteSize {
+        ByteSize(size * EIB)
+    }
+
     #[inline(always)]
     pub const fn as_u64(&self) -> u64 {
         self.0
     }
 
     #[inline(always)]
+    #[deprecated(note = "use `to_string_with` and pass a `UnitSystem` instead")]
     pub fn to_string_as(&self, si_unit: bool) -> String {
+        #[allow(deprecated)]
         to_string(self.0, si_unit)
     }
-}
 
-pub fn to_string(bytes: u64, si_prefix: bool) -> String {
-    let unit = if si_prefix { KIB } else { KB };
-    let unit_base = if si_prefix { LN_KIB } else { LN_KB };
-    let unit_prefix = if si_prefix {
-        UNITS_SI.as_bytes()
-    } else {
-        UNITS.as_bytes()
-    };
-    let unit_suffix = if si_prefix { "iB" } else { "B" };
-
-    if bytes < unit {
-        format!("{} B", bytes)
-    } else {
-        let size = bytes as f64;
-        let exp = match (size.ln() / unit_base) as usize {
-            0 => 1,
-            e => e,
-        };
-
-        format!(
-            "{:.1} {}{}",
-            (size / unit.pow(exp as u32) as f64),
-            unit_prefix[exp - 1] as char,
-            unit_suffix
-        )
-    }
-}
-
-impl Display for ByteSize {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        f.pad(&to_string(self.0, true))
+    #[inline(always)]
+    pub fn to_string_with(&self, system: UnitSystem) -> String {
+        to_string_with(self.0, system)
+    }
+
+    /// Renders the size in the given unit with a fixed number of decimal places.
+    ///
+    /// ```
+    /// use bytesize::{ByteSize, Unit};
+    ///
+    /// let size = ByteSize::gib(1) + ByteSize::mib(512);
+    /// assert_eq!("1536.00 MiB", size.to_string_in(Unit::MiB, 2));
+    /// ```
+    #[inline(always)]
+    pub fn to_string_in(&self, unit: Unit, precision: usize) -> String {
+        self.display().unit(unit).precision(precision).to_string()
     }
-}
 
-impl Debug for ByteSize {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "{}", self)
+    /// Returns the size as a fractional number of the given unit.
+    #[inline(always)]
+    pub fn as_unit_f64(&self, unit: Unit) -> f64 {
+        self.0 as f64 / unit.factor() as f64
     }
-}
 
-macro_rules! commutative_op {
-    ($t:ty) => {
-        impl Add<ByteSize> for $t {
-            type Output = ByteSize;
-            #[inline(always)]
-            fn add(self, rhs: ByteSize) -> ByteSize {
-                ByteSize(rhs.0 + (self as u64))
-            }
+    /// Returns an adapter for rendering this size with custom formatting options.
+    #[inline(always)]
+    pub const fn display(&self) -> Display {
+        Display::new(self.0 as u128)
+    }
+
+    /// Multiplies by a float, rounding to the nearest byte.
+    ///
+    /// The product is computed exactly rather than through `f64`, so sizes beyond 2<sup>53</sup>
+    /// bytes keep their precision. Saturates like an `as` cast: results too large become
+    /// `u64::MAX` bytes, while NaN and negative factors give zero.
+    ///
+    /// ```
+    /// use bytesize::ByteSize;
+    ///
+    /// assert_eq!(ByteSize::gib(8).mul_f64(1.5), ByteSize::gib(12));
+    /// assert_eq!(ByteSize::gib(8).mul_f64(-1.0), ByteSize(0));
+    /// ```
+    #[inline]
+    pub fn mul_f64(self, rhs: f64) -> ByteSize {
+        match self.checked_mul_f64(rhs) {
+            Some(size) => size,
+            None if rhs > 0.0 && self.0 > 0 => ByteSize(u64::MAX),
+            None => ByteSize(0),
         }
+    }
 
-        impl Mul<ByteSize> for $t {
-            type Output = ByteSize;
-            #[inline(always)]
-            fn mul(self, rhs: ByteSize) -> ByteSize {
-                ByteSize(rhs.0 * (self as u64))
-            }
+    /// Multiplies by a float, like [`mul_f64()`](Self::mul_f64).
+    #[inline]
+    pub fn mul_f32(self, rhs: f32) -> ByteSize {
+        self.mul_f64(rhs as f64)
+    }
+
+    /// Multiplies by a float, rounding to the nearest byte. Returns `None` if `rhs` is NaN,
+    /// negative or infinite, or if the result does not fit.
+    #[inline]
+    pub fn checked_mul_f64(self, rhs: f64) -> Option<ByteSize> {
+        arithmetic::scale(self.0, rhs, 1, false).map(ByteSize)
+    }
+
+    /// Divides by a float, rounding to the nearest byte.
+    ///
+    /// Saturates like an `as` cast: dividing a non-zero size by zero or by a tiny factor
+    /// gives `u64::MAX` bytes, while NaN and negative divisors, `-0.0` included, give zero.
+    ///
+    /// ```
+    /// use bytesize::ByteSize;
+    ///
+    /// assert_eq!(ByteSize::gib(3).div_f64(1.5), ByteSize::gib(2));
+    /// assert_eq!(ByteSize::b(10).div_f64(4.0), ByteSize::b(3));
+    /// ```
+    #[inline]
+    pub fn div_f64(self, rhs: f64) -> ByteSize {
+        match self.checked_div_f64(rhs) {
+            Some(size) => size,
+            None if rhs >= 0.0 && rhs.is_sign_positive() && self.0 > 0 => ByteSize(u64::MAX),
+            None => ByteSize(0),
         }
-    };
-}
-
-commutative_op!(u64);
-commutative_op!(u32);
-commutative_op!(u16);
-commutative_op!(u8);
-
-impl Add<ByteSize> for ByteSize {
-    type Output = ByteSize;
-
-    #[inline(always)]
-    fn add(self, rhs: ByteSize) -> ByteSize {
-        ByteSize(self.0 + rhs.0)
     }
-}
 
-impl AddAssign<ByteSize> for ByteSize {
-    #[inline(always)]
-    fn add_assign(&mut self, rhs: ByteSize) {
-        self.0 += rhs.0
+    /// Divides by a float, like [`div_f64()`](Self::div_f64).
+    #[inline]
+    pub fn div_f32(self, rhs: f32) -> ByteSize {
+        self.div_f64(rhs as f64)
     }
-}
 
-impl<T> Add<T> for ByteSize
-where
-    T: Into<u64>,
-{
-    type Output = ByteSize;
-    #[inline(always)]
-    fn add(self, rhs: T) -> ByteSize {
-        ByteSize(self.0 + (rhs.into()))
+    /// Divides by a float, rounding to the nearest byte. Returns `None` if `rhs` is NaN,
+    /// negative or zero, or if the result does not fit.
+    #[inline]
+    pub fn checked_div_f64(self, rhs: f64) -> Option<ByteSize> {
+        if rhs == f64::INFINITY {
+            return Some(ByteSize(0));
+        }
+        arithmetic::scale(self.0, rhs, 1, true).map(ByteSize)
+    }
+
+    /// Returns `percent` percent of the size, rounded to the nearest byte and saturating
+    /// like [`mul_f64()`](Self::mul_f64).
+    ///
+    /// ```
+    /// use bytesize::ByteSize;
+    ///
+    /// assert_eq!(ByteSize::gib(16).percent(75.0), ByteSize::gib(12));
+    /// assert_eq!(ByteSize::kb(1).percent(0.1), ByteSize::b(1));
+    /// ```
+    #[inline]
+    pub fn percent(self, percent: f64) -> ByteSize {
+        match arithmetic::scale(self.0, percent, 100, false) {
+            Some(bytes) => ByteSize(bytes),
+            None if percent > 0.0 && self.0 > 0 => ByteSize(u64::MAX),
+            None => ByteSize(0),
+        }
     }
 }
 
-impl<T> AddAssign<T> for ByteSize
-where
-    T: Into<u64>,
-{
-    #[inline(always)]
-    fn add_assign(&mut self, rhs: T) {
-        self.0 += rhs.into();
-    }
+/// Unit system used when rendering a byte count as human-readable text.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum UnitSystem {
+    /// Decimal (SI) units in powers of 1000: KB, MB, GB, ...
+    Decimal,
+    /// Binary (IEC) units in powers of 1024: KiB, MiB, GiB, ...
+    #[default]
+    Binary,
+    /// JEDEC units in powers of 1024, written without the "i": KB, MB, GB, ...
+    Jedec,
 }
 
-impl Sub<ByteSize> for ByteSize {
-    type Output = ByteSize;
+impl UnitSystem {
+    /// Alias for [`UnitSystem::Decimal`].
+    pub const SI: UnitSystem = UnitSystem::Decimal;
+    /// Alias for [`UnitSystem::Binary`].
+    pub const IEC: UnitSystem = UnitSystem::Binary;
 
+    /// Number of bytes in one step of this unit system (1000 or 1024).
     #[inline(always)]
-    fn sub(self, rhs: ByteSize) -> ByteSize {
-        ByteSize(self.0 - rhs.0)
+    pub const fn base(self) -> u64 {
+        match self {
+            UnitSystem::Decimal => KB,
+            UnitSystem::Binary | UnitSystem::Jedec => KIB,
+        }
     }
 }
 
-impl SubAssign<ByteSize> for ByteSize {
-    #[inline(always)]
-    fn sub_assign(&mut self, rhs: ByteSize) {
-        self.0 -= rhs.0
-    }
+#[deprecated(note = "use `to_string_with` and pass a `UnitSystem` instead")]
+pub fn to_string(bytes: u64, si_prefix: bool) -> String {
+    let system = if si_prefix {
+        UnitSystem::Binary
+    } else {
+        UnitSystem::Decimal
+    };
+    to_string_with(bytes, system)
 }
 
-impl<T> Sub<T> for ByteSize
-where
-    T: Into<u64>,
-{
-    type Output = ByteSize;
-    #[inline(always)]
-    fn sub(self, rhs: T) -> ByteSize {
-        ByteSize(self.0 - (rhs.into()))
-    }
+pub fn to_string_with(bytes: u64, system: UnitSystem) -> String {
+    ByteSize(bytes).display().system(system).to_string()
 }
 
-impl<T> SubAssign<T> for ByteSize
-where
-    T: Into<u64>,
-{
-    #[inline(always)]
-    fn sub_assign(&mut self, rhs: T) {
-        self.0 -= rhs.into();
+/// Formats the size in binary units, with `{:#}` switching to decimal units.
+///
+/// Precision (`{:.2}`) sets the number of decimal places and `{:+}` adds a leading plus sign.
+impl fmt::Display for ByteSize {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let display = if f.alternate() {
+            self.display().decimal()
+        } else {
+            self.display()
+        };
+        fmt::Display::fmt(&display, f)
     }
 }
 
-impl<T> Mul<T> for ByteSize
-where
-    T: Into<u64>,
-{
-    type Output = ByteSize;
-    #[inline(always)]
-    fn mul(self, rhs: T) -> ByteSize {
-        ByteSize(self.0 * rhs.into())
+impl Debug for ByteSize {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self)
     }
 }
 
-impl<T> MulAssign<T> for ByteSize
-where
-    T: Into<u64>,
-{
-    #[inline(always)]
-    fn mul_assign(&mut self, rhs: T) {
-        self.0 *= rhs.into();
-    }
-}
+radix_fmt!(ByteSize);
+
+arithmetic!(ByteSize(u64));
+
+operators!(ByteSize(u64), u64, u32, u16, u8);
 
 #[cfg(feature = "serde")]
 impl<'de> Deserialize<'de> for ByteSize {
@@ -457,6 +534,122 @@ mod tests {
         assert_eq!(x.as_u64(), 3_000_011);
     }
 
+    #[test]
+    fn test_checked_arithmetic() {
+        let free = ByteSize::gib(1);
+        let used = ByteSize::gib(3);
+        let max = ByteSize(u64::MAX);
+
+        assert_eq!(free.checked_sub(used), None);
+        assert_eq!(used.checked_sub(free), Some(ByteSize::gib(2)));
+        assert_eq!(free.saturating_sub(used), ByteSize(0));
+        assert_eq!(free.wrapping_sub(used), ByteSize(u64::MAX - (2 << 30) + 1));
+        assert_eq!(free.overflowing_sub(used), (free.wrapping_sub(used), true));
+        assert_eq!(free.abs_diff(used), ByteSize::gib(2));
+        assert_eq!(used.abs_diff(free), ByteSize::gib(2));
+
+        assert_eq!(max.checked_add(ByteSize(1)), None);
+        assert_eq!(free.checked_add(used), Some(ByteSize::gib(4)));
+        assert_eq!(max.saturating_add(free), max);
+        assert_eq!(max.wrapping_add(ByteSize(2)), ByteSize(1));
+        assert_eq!(max.overflowing_add(ByteSize(0)), (max, false));
+
+        assert_eq!(max.checked_mul(2), None);
+        assert_eq!(free.checked_mul(3), Some(used));
+        assert_eq!(max.saturating_mul(2), max);
+        assert_eq!(max.wrapping_mul(2), ByteSize(u64::MAX - 1));
+        assert_eq!(max.overflowing_mul(2), (ByteSize(u64::MAX - 1), true));
+
+        assert_eq!(max.checked_add_bytes(1), None);
+        assert_eq!(free.checked_sub_bytes(1), Some(ByteSize(free.0 - 1)));
+        assert_eq!(ByteSize(0).saturating_sub_bytes(1), ByteSize(0));
+        assert_eq!(max.saturating_add_bytes(1), max);
+        assert_eq!(ByteSize(0).wrapping_sub_bytes(1), max);
+        assert_eq!(max.overflowing_add_bytes(1), (ByteSize(0), true));
+
+        const HEADROOM: Option<ByteSize> = ByteSize::gib(8).checked_sub(ByteSize::gib(6));
+        assert_eq!(HEADROOM, Some(ByteSize::gib(2)));
+    }
+
+    #[test]
+    fn test_division() {
+        let budget = ByteSize::gib(10);
+        let chunk = ByteSize::mib(300);
+
+        assert_eq!(budget / 4u8, ByteSize::mib(2560));
+        assert_eq!(budget / chunk, 34);
+        assert_eq!(budget % chunk, ByteSize::mib(40));
+        assert_eq!(budget.div_ceil(chunk), 35);
+        assert_eq!(ByteSize::mib(600).div_ceil(chunk), 2);
+        assert_eq!(ByteSize::gib(1).ratio(ByteSize::gib(4)), 0.25);
+
+        let mut size = budget;
+        size /= 5u32;
+        assert_eq!(size, ByteSize::gib(2));
+        size %= ByteSize::mib(1500);
+        assert_eq!(size, ByteSize::mib(548));
+
+        assert_eq!(budget.checked_div(0), None);
+        assert_eq!(budget.checked_div(2), Some(ByteSize::gib(5)));
+        assert_eq!(budget.checked_div_size(ByteSize(0)), None);
+        assert_eq!(budget.checked_div_size(chunk), Some(34));
+        assert_eq!(budget.checked_rem(ByteSize(0)), None);
+        assert_eq!(budget.checked_rem(chunk), Some(ByteSize::mib(40)));
+        assert_eq!(budget.checked_div_ceil(ByteSize(0)), None);
+        assert_eq!(budget.checked_div_ceil(chunk), Some(35));
+        assert_eq!(budget.checked_ratio(ByteSize(0)), None);
+        assert_eq!(budget.checked_ratio(budget), Some(1.0));
+    }
+
+    #[test]
+    fn test_float_scaling() {
+        let memory = ByteSize::gib(16);
+
+        assert_eq!(memory.mul_f64(0.75), ByteSize::gib(12));
+        assert_eq!(memory.mul_f32(0.5), ByteSize::gib(8));
+        assert_eq!(memory.div_f64(4.0), ByteSize::gib(4));
+        assert_eq!(memory.div_f32(0.5), ByteSize::gib(32));
+        assert_eq!(memory.percent(12.5), ByteSize::gib(2));
+        assert_eq!(ByteSize(1000).percent(10.0), ByteSize(100));
+
+        // rounds to the nearest byte, ties up
+        assert_eq!(ByteSize(3).mul_f64(0.5), ByteSize(2));
+        assert_eq!(ByteSize(5).mul_f64(0.1), ByteSize(1));
+        assert_eq!(ByteSize(7).div_f64(2.0), ByteSize(4));
+        assert_eq!(ByteSize(1).mul_f64(f64::MIN_POSITIVE), ByteSize(0));
+
+        // exact beyond the 53 bits an f64 holds
+        let odd = ByteSize((1 << 60) + 1);
+        assert_eq!(odd.mul_f64(1.0), odd);
+        assert_eq!(odd.div_f64(1.0), odd);
+        assert_eq!(ByteSize(u64::MAX).mul_f64(1.0), ByteSize(u64::MAX));
+        assert_eq!(ByteSize(u64::MAX).percent(100.0), ByteSize(u64::MAX));
+
+        assert_eq!(memory.checked_mul_f64(f64::NAN), None);
+        assert_eq!(memory.checked_mul_f64(-0.5), None);
+        assert_eq!(memory.checked_mul_f64(f64::INFINITY), None);
+        assert_eq!(memory.checked_mul_f64(1e10), None);
+        assert_eq!(memory.checked_mul_f64(-0.0), Some(ByteSize(0)));
+        assert_eq!(memory.checked_div_f64(0.0), None);
+        assert_eq!(memory.checked_div_f64(1e-10), None);
+        assert_eq!(memory.checked_div_f64(f64::INFINITY), Some(ByteSize(0)));
+        assert_eq!(memory.checked_div_f64(f64::MIN_POSITIVE / 4.0), None);
+
+        assert_eq!(memory.mul_f64(1e10), ByteSize(u64::MAX));
+        assert_eq!(memory.mul_f64(f64::INFINITY), ByteSize(u64::MAX));
+        assert_eq!(memory.mul_f64(f64::NAN), ByteSize(0));
+        assert_eq!(memory.mul_f64(-2.0), ByteSize(0));
+        assert_eq!(ByteSize(0).mul_f64(f64::INFINITY), ByteSize(0));
+        assert_eq!(memory.div_f64(0.0), ByteSize(u64::MAX));
+        assert_eq!(ByteSize(0).div_f64(0.0), ByteSize(0));
+        assert_eq!(memory.div_f64(-1.0), ByteSize(0));
+        assert_eq!(memory.div_f64(-0.0), ByteSize(0));
+        assert_eq!(memory.div_f64(f64::NAN), ByteSize(0));
+        assert_eq!(memory.checked_div_f64(-0.0), None);
+        assert_eq!(memory.percent(f64::NAN), ByteSize(0));
+        assert_eq!(memory.percent(1e12), ByteSize(u64::MAX));
+    }
+
     #[test]
     fn test_comparison() {
         assert!(ByteSize::mb(1) == ByteSize::kb(1000));
@@ -464,6 +657,8 @@ mod tests {
         assert!(ByteSize::mb(1) != ByteSize::kib(1024));
         assert!(ByteSize::mb(1) < ByteSize::kib(1024));
         assert!(ByteSize::b(0) < ByteSize::tib(1));
+        assert!(ByteSize::eb(1) < ByteSize::eib(1));
+        assert_eq!(ByteSize::eib(1), ByteSize::pib(1024));
     }
 
     fn assert_display(expected: &str, b: ByteSize) {
@@ -479,6 +674,8 @@ mod tests {
         assert_display("518.0 GiB", ByteSize::gib(518));
         assert_display("815.0 TiB", ByteSize::tib(815));
         assert_display("609.0 PiB", ByteSize::pib(609));
+        assert_display("15.0 EiB", ByteSize::eib(15));
+        assert_display("16.0 EiB", ByteSize(u64::MAX));
     }
 
     #[test]
@@ -493,10 +690,39 @@ mod tests {
         assert_eq!("|--357 B---|", format!("|{:-^10}|", ByteSize(357)));
     }
 
+    #[test]
+    fn test_display_radix() {
+        let page = ByteSize::kib(4);
+        assert_eq!("1000", format!("{:x}", page));
+        assert_eq!("0x1000", format!("{:#x}", page));
+        assert_eq!("0x00001000", format!("{:#010x}", page));
+        assert_eq!("FF", format!("{:X}", ByteSize(255)));
+        assert_eq!("0o10000", format!("{:#o}", page));
+        assert_eq!("0b100000000", format!("{:#b}", ByteSize(256)));
+    }
+
+    #[test]
+    fn test_display_flags() {
+        assert_eq!("1.500 MiB", format!("{:.3}", ByteSize::kib(1536)));
+        assert_eq!("2 MiB", format!("{:.0}", ByteSize::kib(1536)));
+        assert_eq!("215 B", format!("{:.2}", ByteSize::b(215)));
+
+        assert_eq!("1.6 MB", format!("{:#}", ByteSize::kib(1536)));
+        assert_eq!("1.57 MB", format!("{:#.2}", ByteSize::kib(1536)));
+
+        assert_eq!("+1.5 MiB", format!("{:+}", ByteSize::kib(1536)));
+        assert_eq!("|  +1.57 MB|", format!("|{:>+#10.2}|", ByteSize::kib(1536)));
+    }
+
+    #[allow(deprecated)]
     fn assert_to_string(expected: &str, b: ByteSize, si: bool) {
         assert_eq!(expected.to_string(), b.to_string_as(si));
     }
 
+    fn assert_to_string_with(expected: &str, b: ByteSize, system: UnitSystem) {
+        assert_eq!(expected.to_string(), b.to_string_with(system));
+    }
+
     #[test]
     fn test_to_string_as() {
         assert_to_string("215 B", ByteSize::b(215), true);
@@ -509,7 +735,7 @@ mod tests {
         assert_to_string("301.0 KB", ByteSize::kb(301), false);
 
         assert_to_string("1.0 MiB", ByteSize::mib(1), true);
-        assert_to_string("1048.6 KB", ByteSize::mib(1), false);
+        assert_to_string("1.0 MB", ByteSize::mib(1), false);
 
         // a bug case: https://github.com/flang-project/bytesize/issues/8
         assert_to_string("1.9 GiB", ByteSize::mib(1907), true);
@@ -526,6 +752,38 @@ mod tests {
 
         assert_to_string("540.9 PiB", ByteSize::pb(609), true);
         assert_to_string("609.0 PB", ByteSize::pb(609), false);
+
+        assert_to_string("1.7 EiB", ByteSize::eb(2), true);
+        assert_to_string("2.0 EB", ByteSize::eb(2), false);
+    }
+
+    #[test]
+    fn test_to_string_with() {
+        assert_to_string_with("215 B", ByteSize::b(215), UnitSystem::Decimal);
+        assert_to_string_with("215 B", ByteSize::b(215), UnitSystem::Binary);
+        assert_to_string_with("215 B", ByteSize::b(215), UnitSystem::Jedec);
+
+        assert_to_string_with("301.0 KB", ByteSize::kb(301), UnitSystem::Decimal);
+        assert_to_string_with("293.9 KiB", ByteSize::kb(301), UnitSystem::Binary);
+        assert_to_string_with("293.9 KB", ByteSize::kb(301), UnitSystem::Jedec);
+
+        assert_to_string_with("518.0 GB", ByteSize::gb(518), UnitSystem::SI);
+        assert_to_string_with("482.4 GiB", ByteSize::gb(518), UnitSystem::IEC);
+        assert_to_string_with("482.4 GB", ByteSize::gb(518), UnitSystem::Jedec);
+    }
+
+    #[test]
+    fn test_to_string_in() {
+        assert_eq!("0.50 GiB", ByteSize::mib(512).to_string_in(Unit::GiB, 2));
+        assert_eq!("12 GiB", ByteSize::gib(12).to_string_in(Unit::GiB, 0));
+        assert_eq!("1000.0 KB", ByteSize::mb(1).to_string_in(Unit::KB, 1));
+    }
+
+    #[test]
+    fn test_as_unit_f64() {
+        assert_eq!(0.5, ByteSize::mib(512).as_unit_f64(Unit::GiB));
+        assert_eq!(1.5, ByteSize::kb(1500).as_unit_f64(Unit::MB));
+        assert_eq!(215.0, ByteSize::b(215).as_unit_f64(Unit::B));
     }
 
     #[test]
@@ -0,0 +1,368 @@
+use super::unit::PREFIXES;
+use super::{ByteSize, ByteSize128, ParseErrorKind, Unit};
+
+impl ByteSize {
+    /// Parses a size at compile time, e.g. for `const` items and tables. Usually reached
+    /// through the [`bytesize!`](crate::bytesize) macro.
+    ///
+    /// This is a subset of [`FromStr`](std::str::FromStr): one or more numbers with an
+    /// optional fraction and `_` between digits, each followed by an optional unit, such
+    /// as `"1.5 GiB"` or `"1 GiB 512 MiB"`. Exponents, other digit separators and radix
+    /// prefixes are not supported, and a unit must not run straight into the next number,
+    /// so `"1e9"` is rejected rather than read as 1 EB and 9 B. Fractions of a byte are rounded down, and unlike
+    /// `FromStr`, a fraction rounded up past the largest size such as `"16.0 EiB"` is
+    /// rejected.
+    ///
+    /// ```
+    /// use bytesize::{ByteSize, ParseErrorKind};
+    ///
+    /// const LIMIT: ByteSize = match ByteSize::parse_const("1.5 GiB") {
+    ///     Ok(size) => size,
+    ///     Err(_) => panic!("invalid size"),
+    /// };
+    /// assert_eq!(LIMIT, ByteSize::mib(1536));
+    /// assert_eq!(ByteSize::parse_const("16 EiB"), Err(ParseErrorKind::Overflow));
+    /// ```
+    pub const fn parse_const(s: &str) -> Result<ByteSize, ParseErrorKind> {
+        match parse(s.as_bytes()) {
+            Ok(bytes) if bytes <= u64::MAX as u128 => Ok(ByteSize(bytes as u64)),
+            Ok(_) => Err(ParseErrorKind::Overflow),
+            Err(kind) => Err(kind),
+        }
+    }
+
+    #[doc(hidden)]
+    pub const fn __expect_const(result: Result<ByteSize, ParseErrorKind>) -> ByteSize {
+        match result {
+            Ok(size) => size,
+            Err(kind) => fail(kind),
+        }
+    }
+}
+
+impl ByteSize128 {
+    /// Parses a size at compile time, like [`ByteSize::parse_const()`].
+    pub const fn parse_const(s: &str) -> Result<ByteSize128, ParseErrorKind> {
+        match parse(s.as_bytes()) {
+            Ok(bytes) => Ok(ByteSize128(bytes)),
+            Err(kind) => Err(kind),
+        }
+    }
+
+    #[doc(hidden)]
+    pub const fn __expect_const(result: Result<ByteSize128, ParseErrorKind>) -> ByteSize128 {
+        match result {
+            Ok(size) => size,
+            Err(kind) => fail(kind),
+        }
+    }
+}
+
+/// Size literal checked at compile time: an invalid or overflowing literal fails the build.
+///
+/// Accepts the syntax of [`ByteSize::parse_const()`] and yields a [`ByteSize`], or a
+/// [`ByteSize128`] when prefixed with `u128:`.
+///
+/// ```
+/// use bytesize::{bytesize, ByteSize, ByteSize128};
+///
+/// const LIMITS: [ByteSize; 2] = [bytesize!("4 GiB"), bytesize!("1.5 MB")];
+/// assert_eq!(LIMITS, [ByteSize::gib(4), ByteSize::kb(1500)]);
+/// assert_eq!(bytesize!(u128: "2 ZiB"), ByteSize128::zib(2));
+/// ```
+///
+/// ```compile_fail
+/// let size = bytesize::bytesize!("4 GiBB");
+/// ```
+///
+/// ```compile_fail
+/// let size = bytesize::bytesize!("16 EiB");
+/// ```
+///
+/// ```compile_fail
+/// let size = bytesize::bytesize!("1e9");
+/// ```
+#[macro_export]
+macro_rules! bytesize {
+    ($literal:literal) => {{
+        const SIZE: $crate::ByteSize =
+            $crate::ByteSize::__expect_const($crate::ByteSize::parse_const($literal));
+        SIZE
+    }};
+    (u128: $literal:literal) => {{
+        const SIZE: $crate::ByteSize128 =
+            $crate::ByteSize128::__expect_const($crate::ByteSize128::parse_const($literal));
+        SIZE
+    }};
+}
+
+const fn fail(kind: ParseErrorKind) -> ! {
+    // const panics only take literal messages
+    match kind {
+        ParseErrorKind::Empty => panic!("empty size literal"),
+        ParseErrorKind::InvalidNumber => panic!("invalid number in size literal"),
+        ParseErrorKind::UnknownUnit => panic!("unknown unit in size literal"),
+        ParseErrorKind::FractionalBytes => panic!("size literal is not a whole number of bytes"),
+        ParseErrorKind::Overflow => panic!("size literal is too large"),
+        _ => panic!("invalid size literal"),
+    }
+}
+
+/// Parses `s` into a number of bytes, rounding fractions of a byte down.
+const fn parse(s: &[u8]) -> Result<u128, ParseErrorKind> {
+    let mut i = skip_whitespace(s, 0);
+    if i == s.len() {
+        return Err(ParseErrorKind::Empty);
+    }
+
+    let mut total: u128 = 0;
+    while i < s.len() {
+        // integer part, with `_` allowed between digits
+        let mut whole: u128 = 0;
+        let mut digits = 0;
+        while i < s.len() {
+            if s[i].is_ascii_digit() {
+                whole = match whole.checked_mul(10) {
+                    Some(whole) => match whole.checked_add((s[i] - b'0') as u128) {
+                        Some(whole) => whole,
+                        None => return Err(ParseErrorKind::Overflow),
+                    },
+                    None => return Err(ParseErrorKind::Overflow),
+                };
+                digits += 1;
+            } else if !(s[i] == b'_' && digits > 0 && i + 1 < s.len() && s[i + 1].is_ascii_digit())
+            {
+                break;
+            }
+            i += 1;
+        }
+
+        // fraction, evaluated once the unit is known
+        let fraction = i;
+        if i < s.len() && s[i] == b'.' {
+            i += 1;
+            while i < s.len() && s[i].is_ascii_digit() {
+                digits += 1;
+                i += 1;
+            }
+        }
+        if digits == 0 || i < s.len() && s[i] == b'.' {
+            return Err(ParseErrorKind::InvalidNumber);
+        }
+        let fraction_end = i;
+
+        i = skip_whitespace(s, i);
+        let unit_start = i;
+        while i < s.len() && s[i].is_ascii_alphabetic() {
+            i += 1;
+        }
+        let (unit, bits) = match parse_unit(s, unit_start, i) {
+            Some(unit) => unit,
+            None => return Err(ParseErrorKind::UnknownUnit),
+        };
+        // `1e9` would be an exponent to `FromStr`, not 1 EB and 9 B
+        if i > unit_start && i < s.len() && s[i].is_ascii_digit() {
+            return Err(ParseErrorKind::InvalidNumber);
+        }
+        // only the last number may omit its unit, like `FromStr`
+        let unitless = i == unit_start;
+        i = skip_whitespace(s, i);
+        if unitless && i < s.len() {
+            return Err(ParseErrorKind::InvalidNumber);
+        }
+
+        let factor = unit.factor();
+        let mut amount = match whole.checked_mul(factor) {
+            Some(amount) => amount,
+            None => return Err(ParseErrorKind::Overflow),
+        };
+        // Horner's scheme from the last digit, truncating at every step
+        let mut part: u128 = 0;
+        let mut exact = true;
+        let mut j = fraction_end;
+        while j > fraction + 1 {
+            j -= 1;
+            let n = (s[j] - b'0') as u128 * factor + part;
+            exact = exact && n % 10 == 0;
+            part = n / 10;
+        }
+        amount = match amount.checked_add(part) {
+            Some(amount) => amount,
+            None => return Err(ParseErrorKind::Overflow),
+        };
+        if bits {
+            if !exact || amount % 8 != 0 {
+                return Err(ParseErrorKind::FractionalBytes);
+            }
+            amount /= 8;
+        }
+
+        total = match total.checked_add(amount) {
+            Some(total) => total,
+            None => return Err(ParseErrorKind::Overflow),
+        };
+    }
+    Ok(total)
+}
+
+/// Parses the unit in `s[start..end]` like `FromStr` does: case-insensitive except for the
+/// final `b` (bits) or `B` (bytes).
+const fn parse_unit(s: &[u8], start: usize, end: usize) -> Option<(Unit, bool)> {
+    let (end, bits) = if ends_with(s, start, end, b"bytes") {
+        (end - 5, false)
+    } else if ends_with(s, start, end, b"byte") {
+        (end - 4, false)
+    } else if ends_with(s, start, end, b"bits") {
+        (end - 4, true)
+    } else if ends_with(s, start, end, b"bit") {
+        (end - 3, true)
+    } else if end > start && s[end - 1] == b'B' {
+        (end - 1, false)
+    } else if end > start && s[end - 1] == b'b' {
+        (end - 1, true)
+    } else {
+        (end, false)
+    };
+
+    let mut k = 0;
+    while k < PREFIXES.len() {
+        let (prefix, unit) = PREFIXES[k];
+        if prefix.len() == end - start && ends_with(s, start, end, prefix.as_bytes()) {
+            return Some((unit, bits));
+        }
+        k += 1;
+    }
+    None
+}
+
+/// Whether `s[start..end]` ends with the lower case `word`, ignoring ASCII case.
+const fn ends_with(s: &[u8], start: usize, end: usize, word: &[u8]) -> bool {
+    if end - start < word.len() {
+        return false;
+    }
+    let offset = end - word.len();
+    let mut k = 0;
+    while k < word.len() {
+        if s[offset + k].to_ascii_lowercase() != word[k] {
+            return false;
+        }
+        k += 1;
+    }
+    true
+}
+
+const fn skip_whitespace(s: &[u8], mut i: usize) -> usize {
+    while i < s.len() && s[i].is_ascii_whitespace() {
+        i += 1;
+    }
+    i
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_const() {
+        const TABLE: [ByteSize; 4] = [
+            bytesize!("4 GiB"),
+            bytesize!("1.5 GiB"),
+            bytesize!("1 GiB 512 MiB"),
+            bytesize!("8 Mbit"),
+        ];
+        assert_eq!(
+            TABLE,
+            [
+                ByteSize::gib(4),
+                ByteSize::mib(1536),
+                ByteSize::mib(1536),
+                ByteSize::mb(1)
+            ]
+        );
+        assert_eq!(bytesize!("1_000_000"), ByteSize::mb(1));
+        assert_eq!(bytesize!(" 2 kilobytes "), ByteSize::kb(2));
+        assert_eq!(bytesize!("0.1 KiB"), ByteSize::b(102));
+        assert_eq!(bytesize!(".5 KiB"), ByteSize::b(512));
+        assert_eq!(bytesize!("18446744073709551615"), ByteSize(u64::MAX));
+        assert_eq!(
+            bytesize!(u128: "1.5 QB"),
+            ByteSize128(3 * Unit::QB.factor() / 2)
+        );
+
+        // agrees with the runtime parser
+        for input in [
+            "0",
+            "1K",
+            "1.5 MiB",
+            "3 megabytes",
+            "1 Gi 1 B",
+            "24 bits",
+            "7 Kib",
+            "1 KiB 1",
+            "2 kibibytes 1 KB",
+        ] {
+            assert_eq!(
+                ByteSize::parse_const(input),
+                Ok(input.parse::<ByteSize>().unwrap()),
+                "{}",
+                input
+            );
+        }
+
+        assert_eq!(ByteSize::parse_const(""), Err(ParseErrorKind::Empty));
+        assert_eq!(ByteSize::parse_const("  "), Err(ParseErrorKind::Empty));
+        assert_eq!(
+            ByteSize::parse_const("GiB"),
+            Err(ParseErrorKind::InvalidNumber)
+        );
+        assert_eq!(
+            ByteSize::parse_const("1.2.3"),
+            Err(ParseErrorKind::InvalidNumber)
+        );
+        assert_eq!(
+            ByteSize::parse_const("1__0"),
+            Err(ParseErrorKind::InvalidNumber)
+        );
+        assert_eq!(
+            ByteSize::parse_const("1 GiBB"),
+            Err(ParseErrorKind::UnknownUnit)
+        );
+        assert_eq!(
+            ByteSize::parse_const("1 GiB!"),
+            Err(ParseErrorKind::InvalidNumber)
+        );
+        for input in ["1e9", ".2E9", "2e1", "1GiB512MiB"] {
+            assert_eq!(
+                ByteSize::parse_const(input),
+                Err(ParseErrorKind::InvalidNumber),
+                "{}",
+                input
+            );
+        }
+        assert_eq!(
+            ByteSize::parse_const("12 bit"),
+            Err(ParseErrorKind::FractionalBytes)
+        );
+        assert_eq!(
+            ByteSize::parse_const("16 EiB"),
+            Err(ParseErrorKind::Overflow)
+        );
+        assert_eq!(
+            ByteSize128::parse_const("400000000 QB"),
+            Err(ParseErrorKind::Overflow)
+        );
+        // the last digit carries past the largest size
+        assert_eq!(
+            ByteSize128::parse_const("340282366920938463463374607431768211459"),
+            Err(ParseErrorKind::Overflow)
+        );
+        for input in ["1 000", "1 2", "5 5 KiB"] {
+            assert_eq!(
+                ByteSize::parse_const(input),
+                Err(ParseErrorKind::InvalidNumber),
+                "{}",
+                input
+            );
+        }
+    }
+}
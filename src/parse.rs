@@ -1,182 +1,903 @@
-use super::ByteSize;
+use std::fmt;
+use std::ops::Range;
 
-impl std::str::FromStr for ByteSize {
-    type Err = String;
+use super::unit::PREFIXES;
+use super::{ByteSize, ByteSize128, ByteSizeDelta, Display, Unit, UnitSystem};
 
-    fn from_str(value: &str) -> Result<Self, Self::Err> {
-        if let Ok(v) = value.parse::<u64>() {
-            return Ok(Self(v));
+/// What went wrong while parsing a size or a unit.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// The input is empty or only whitespace.
+    Empty,
+    /// The number is missing or malformed, e.g. `"1.2.3 KiB"`.
+    InvalidNumber,
+    /// The unit is not known, e.g. `"1 KiBB"`.
+    UnknownUnit,
+    /// A bit count that does not fill whole bytes, e.g. `"12 bit"`, or a fractional byte
+    /// count where [`ParseOptions::exact()`] requires whole bytes, e.g. `"0.1 KiB"`.
+    FractionalBytes,
+    /// An expression that does not follow the grammar of [`ParseOptions::eval()`], that
+    /// multiplies two sizes or that nests parentheses too deeply, e.g. `"2 GiB +"` or
+    /// `"2 GiB * 2 GiB"`.
+    InvalidExpression,
+    /// An expression divides by zero, e.g. `"1 GiB / 0"`.
+    DivideByZero,
+    /// A unit rejected by [`ParseOptions::strict()`] as ambiguous, e.g. `"4K"`.
+    AmbiguousUnit,
+    /// A bit unit where a byte unit is required, e.g. `"Mb"` parsed as a [`Unit`].
+    BitUnit,
+    /// The size does not fit the target type.
+    Overflow,
+    /// An expression evaluates to a negative size, e.g. `"1 MiB - 1 GiB"`.
+    Negative,
+}
+
+/// Error returned when parsing a [`ByteSize`], [`ByteSize128`] or [`Unit`] fails.
+///
+/// ```
+/// use bytesize::{ByteSize, ParseErrorKind};
+///
+/// let error = "1.5 MiBB".parse::<ByteSize>().unwrap_err();
+/// assert_eq!(error.kind(), ParseErrorKind::UnknownUnit);
+/// assert_eq!(error.span(), 4..8);
+/// assert_eq!(error.suggestion(), Some("MiB"));
+/// assert_eq!(error.to_string(), "unknown unit \"MiBB\", did you mean \"MiB\"?");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    kind: ParseErrorKind,
+    span: Range<usize>,
+    fragment: String,
+    suggestion: Option<&'static str>,
+}
+
+impl ParseError {
+    pub(crate) fn new(kind: ParseErrorKind, input: &str, span: Range<usize>) -> ParseError {
+        ParseError {
+            kind,
+            fragment: input[span.clone()].to_string(),
+            span,
+            suggestion: None,
         }
-        let number = take_while(value, |c| c.is_ascii_digit() || c == '.');
-        match number.parse::<f64>() {
-            Ok(v) => {
-                let suffix = skip_while(value, |c| {
-                    c.is_whitespace() || c.is_ascii_digit() || c == '.'
-                });
-                match suffix.parse::<Unit>() {
-                    Ok(u) => Ok(Self((v * u) as u64)),
-                    Err(error) => Err(format!(
-                        "couldn't parse {:?} into a known SI unit, {}",
-                        suffix, error
-                    )),
+    }
+
+    /// Moves the span of an error found in a suffix of the input that starts at `offset`.
+    fn offset(mut self, offset: usize) -> ParseError {
+        self.span = self.span.start + offset..self.span.end + offset;
+        self
+    }
+
+    /// What went wrong.
+    pub fn kind(&self) -> ParseErrorKind {
+        self.kind
+    }
+
+    /// Byte range of the offending part of the input.
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// A known unit close to an unknown one, e.g. `"MiB"` for `"MiBB"`.
+    pub fn suggestion(&self) -> Option<&'static str> {
+        self.suggestion
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ParseErrorKind::Empty => f.write_str("cannot parse a size from an empty string"),
+            ParseErrorKind::InvalidNumber => write!(f, "invalid number {:?}", self.fragment),
+            ParseErrorKind::UnknownUnit => {
+                write!(f, "unknown unit {:?}", self.fragment)?;
+                match self.suggestion {
+                    Some(suggestion) => write!(f, ", did you mean {:?}?", suggestion),
+                    None => Ok(()),
                 }
             }
-            Err(error) => Err(format!(
-                "couldn't parse {:?} into a ByteSize, {}",
-                value, error
-            )),
+            ParseErrorKind::InvalidExpression if self.fragment.is_empty() => {
+                f.write_str("unexpected end of expression")
+            }
+            ParseErrorKind::InvalidExpression => {
+                write!(f, "unexpected {:?} in expression", self.fragment)
+            }
+            ParseErrorKind::DivideByZero => write!(f, "division by zero in {:?}", self.fragment),
+            ParseErrorKind::AmbiguousUnit => {
+                write!(f, "ambiguous unit {:?}", self.fragment)?;
+                match self.suggestion {
+                    Some(suggestion) => write!(f, ", did you mean {:?}?", suggestion),
+                    None => Ok(()),
+                }
+            }
+            ParseErrorKind::FractionalBytes => {
+                write!(f, "{:?} is not a whole number of bytes", self.fragment)
+            }
+            ParseErrorKind::BitUnit => {
+                write!(f, "{:?} is a bit unit, not a byte unit", self.fragment)
+            }
+            ParseErrorKind::Overflow => write!(f, "{:?} is too large", self.fragment),
+            ParseErrorKind::Negative => write!(f, "{:?} is negative", self.fragment),
         }
     }
 }
 
-fn take_while<P>(s: &str, mut predicate: P) -> &str
-where
-    P: FnMut(char) -> bool,
-{
-    let offset = s
-        .chars()
-        .take_while(|ch| predicate(*ch))
-        .map(|ch| ch.len_utf8())
-        .sum();
-    &s[..offset]
+impl std::error::Error for ParseError {}
+
+/// Settings for parsing sizes, for callers that need a different policy than [`FromStr`].
+///
+/// The defaults match [`FromStr`]: surrounding whitespace is trimmed, unit-less numbers are
+/// bytes, unit prefixes are case-insensitive and `K`, `KB` or `kilobyte` mean 1000 bytes.
+///
+/// ```
+/// use bytesize::{ByteSize, ParseOptions, Unit, UnitSystem};
+///
+/// let options = ParseOptions::new().default_unit(Unit::MiB);
+/// assert_eq!(options.parse("512").unwrap().size, ByteSize::mib(512));
+///
+/// let parsed = ParseOptions::new().system(UnitSystem::Jedec).parse("4K").unwrap();
+/// assert_eq!(parsed.size, ByteSize::kib(4));
+/// assert_eq!(parsed.unit, Unit::KiB);
+/// assert!(parsed.ambiguous);
+///
+/// assert!(ParseOptions::new().strict(true).parse("4K").is_err());
+/// assert!(ParseOptions::new().strict(true).parse("4 KiB").is_ok());
+/// ```
+///
+/// [`FromStr`]: std::str::FromStr
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ParseOptions {
+    strict: bool,
+    pub(crate) exact: bool,
+    pub(crate) default_unit: Unit,
+    pub(crate) trim: bool,
+    case_sensitive: bool,
+    system: UnitSystem,
+    pub(crate) separators: &'static [char],
 }
 
-fn skip_while<P>(s: &str, mut predicate: P) -> &str
-where
-    P: FnMut(char) -> bool,
-{
-    let offset: usize = s
-        .chars()
-        .skip_while(|ch| predicate(*ch))
-        .map(|ch| ch.len_utf8())
-        .sum();
-    &s[(s.len() - offset)..]
+/// A size read by [`ParseOptions`], together with how its units were interpreted.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Parsed<T> {
+    /// The parsed size.
+    pub size: T,
+    /// The unit chosen for the first ambiguous component, or for the first component if
+    /// none is ambiguous.
+    pub unit: Unit,
+    /// Whether that unit was read as counting bits.
+    pub bits: bool,
+    /// Whether any unit was written ambiguously, such as `K` (kilobyte or kibibyte?), `m`
+    /// (milli or mega?) or `kb` (kilobit or kilobyte?).
+    pub ambiguous: bool,
 }
 
-enum Unit {
-    Byte,
-    // power of tens
-    KiloByte,
-    MegaByte,
-    GigaByte,
-    TeraByte,
-    PetaByte,
-    // power of twos
-    KibiByte,
-    MebiByte,
-    GibiByte,
-    TebiByte,
-    PebiByte,
+/// One number and its unit within a size.
+struct Component {
+    span: Range<usize>,
+    /// The size in bytes, rounded down to a whole byte.
+    bytes: u128,
+    unit: Unit,
+    bits: bool,
+    ambiguous: bool,
 }
 
-impl Unit {
-    fn factor(&self) -> u64 {
-        match self {
-            Self::Byte => super::B,
-            // power of tens
-            Self::KiloByte => super::KB,
-            Self::MegaByte => super::MB,
-            Self::GigaByte => super::GB,
-            Self::TeraByte => super::TB,
-            Self::PetaByte => super::PB,
-            // power of twos
-            Self::KibiByte => super::KIB,
-            Self::MebiByte => super::MIB,
-            Self::GibiByte => super::GIB,
-            Self::TebiByte => super::TIB,
-            Self::PebiByte => super::PIB,
+impl ParseOptions {
+    /// Options matching [`FromStr`](std::str::FromStr).
+    pub const fn new() -> ParseOptions {
+        ParseOptions {
+            strict: false,
+            exact: false,
+            default_unit: Unit::B,
+            trim: true,
+            case_sensitive: false,
+            system: UnitSystem::Decimal,
+            separators: &['_', ',', '\u{2009}'],
         }
     }
-}
 
-mod impl_ops {
-    use super::Unit;
-    use std::ops;
+    /// Reject ambiguous units instead of guessing: bare prefixes such as `K`, miscased ones
+    /// such as `m` or `kiB`, and all-lowercase bit units such as `kb`. Defaults to `false`.
+    #[inline(always)]
+    pub const fn strict(mut self, strict: bool) -> ParseOptions {
+        self.strict = strict;
+        self
+    }
 
-    impl ops::Add<u64> for Unit {
-        type Output = u64;
+    /// Reject sizes that are not a whole number of bytes, such as `0.1 KiB`, instead of
+    /// rounding them down. Defaults to `false`.
+    #[inline(always)]
+    pub const fn exact(mut self, exact: bool) -> ParseOptions {
+        self.exact = exact;
+        self
+    }
 
-        fn add(self, other: u64) -> Self::Output {
-            self.factor() + other
+    /// Unit of numbers written without one. Defaults to [`Unit::B`].
+    #[inline(always)]
+    pub const fn default_unit(mut self, unit: Unit) -> ParseOptions {
+        self.default_unit = unit;
+        self
+    }
+
+    /// Ignore whitespace around the size. Defaults to `true`.
+    #[inline(always)]
+    pub const fn trim(mut self, trim: bool) -> ParseOptions {
+        self.trim = trim;
+        self
+    }
+
+    /// Require unit prefixes in their usual case (`K` or `k`, `M`, `Ki`, ...) instead of
+    /// accepting any case. The final `b` or `B` always tells bits from bytes. Defaults to
+    /// `false`.
+    #[inline(always)]
+    pub const fn case_sensitive(mut self, case_sensitive: bool) -> ParseOptions {
+        self.case_sensitive = case_sensitive;
+        self
+    }
+
+    /// What the decimal-looking symbols `K`, `KB`, `MB`, ... mean: powers of 1000 for
+    /// [`UnitSystem::Decimal`], powers of 1024 otherwise. Spelled-out names such as
+    /// `kilobyte` are always decimal. Defaults to [`UnitSystem::Decimal`].
+    #[inline(always)]
+    pub const fn system(mut self, system: UnitSystem) -> ParseOptions {
+        self.system = system;
+        self
+    }
+
+    /// Characters accepted as thousands separators in the integer part of a number, such
+    /// as `1_000_000` or `1,048,576`. Groups after the first must have exactly three
+    /// digits, so `1,5` is rejected rather than read as 15. Defaults to underscore, comma
+    /// and thin space (U+2009).
+    ///
+    /// # Panics
+    ///
+    /// Panics if a separator could be part of a number itself: an ASCII letter or digit, the
+    /// decimal point or a sign.
+    #[inline(always)]
+    pub const fn digit_separators(mut self, separators: &'static [char]) -> ParseOptions {
+        let mut i = 0;
+        while i < separators.len() {
+            let c = separators[i];
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '+' | '-') {
+                panic!("digit separators must not be letters, digits, '.', '+' or '-'");
+            }
+            i += 1;
+        }
+        self.separators = separators;
+        self
+    }
+
+    /// Parses a [`ByteSize`].
+    ///
+    /// The largest size as [`Display`](crate::Display) renders it, such as "16.0 EiB" for
+    /// `u64::MAX` bytes, is read as the largest size even though the written number is
+    /// rounded up past it, unless [`exact()`](Self::exact) is set.
+    pub fn parse(&self, input: &str) -> Result<Parsed<ByteSize>, ParseError> {
+        let parsed = self.parse_bounded(input, u64::MAX.into())?;
+        Ok(Parsed {
+            size: ByteSize(parsed.size as u64),
+            unit: parsed.unit,
+            bits: parsed.bits,
+            ambiguous: parsed.ambiguous,
+        })
+    }
+
+    /// Parses a [`ByteSize128`], reading the largest size as displayed like
+    /// [`parse()`](Self::parse).
+    pub fn parse128(&self, input: &str) -> Result<Parsed<ByteSize128>, ParseError> {
+        let parsed = self.parse_bounded(input, u128::MAX)?;
+        Ok(Parsed {
+            size: ByteSize128(parsed.size),
+            unit: parsed.unit,
+            bits: parsed.bits,
+            ambiguous: parsed.ambiguous,
+        })
+    }
+
+    /// Parses a size of at most `max` bytes, like [`parse()`](Self::parse).
+    pub(crate) fn parse_bounded(&self, input: &str, max: u128) -> Result<Parsed<u128>, ParseError> {
+        let overflow = |span| ParseError::new(ParseErrorKind::Overflow, input, span);
+        let mut total: u128 = 0;
+        let mut clamped = false;
+        let parsed = self.fold(input, |component| {
+            let mut bytes = component.bytes;
+            if bytes > max {
+                if total != 0 || !self.renders_max(input, &component, max) {
+                    return Err(overflow(component.span));
+                }
+                clamped = true;
+                bytes = max;
+            } else if clamped {
+                // nothing can be added to the largest size
+                return Err(overflow(0..input.len()));
+            }
+            total = total
+                .checked_add(bytes)
+                .filter(|&total| total <= max)
+                .ok_or_else(|| overflow(0..input.len()))?;
+            Ok(())
+        })?;
+        Ok(Parsed {
+            size: total,
+            unit: parsed.unit,
+            bits: parsed.bits,
+            ambiguous: parsed.ambiguous,
+        })
+    }
+
+    /// Whether `component` is written exactly as [`Display`](crate::Display) renders `max`
+    /// bytes in its unit, i.e. it only exceeds `max` through the rounding of the last place.
+    fn renders_max(&self, input: &str, component: &Component, max: u128) -> bool {
+        let value = &input[component.span.clone()];
+        let number = &value[..number_len(value, self.separators)];
+        !self.exact && !component.bits && renders_max(number, component.unit, max)
+    }
+
+    /// Feeds each component of `input` to `add`, returning the interpretation to report.
+    fn fold<F>(&self, input: &str, mut add: F) -> Result<Parsed<()>, ParseError>
+    where
+        F: FnMut(Component) -> Result<(), ParseError>,
+    {
+        let mut parsed: Option<Parsed<()>> = None;
+        for component in self.components(input)? {
+            let component = component?;
+            if parsed.map_or(true, |parsed| !parsed.ambiguous && component.ambiguous) {
+                parsed = Some(Parsed {
+                    size: (),
+                    unit: component.unit,
+                    bits: component.bits,
+                    ambiguous: component.ambiguous,
+                });
+            }
+            add(component)?;
+        }
+        Ok(parsed.expect("a size has at least one component"))
+    }
+
+    /// Splits a size into its components, so "1GiB 512MiB" and "1G512M" are read as two
+    /// sizes to be added up.
+    fn components<'a>(
+        &'a self,
+        input: &'a str,
+    ) -> Result<impl Iterator<Item = Result<Component, ParseError>> + 'a, ParseError> {
+        let (start, end) = if self.trim {
+            let start = input.len() - input.trim_start().len();
+            (start, start.max(input.trim_end().len()))
+        } else {
+            (0, input.len())
+        };
+        if start == end {
+            return Err(ParseError::new(
+                ParseErrorKind::Empty,
+                input,
+                0..input.len(),
+            ));
+        }
+
+        let mut offset = start;
+        Ok(std::iter::from_fn(move || {
+            if offset == end {
+                return None;
+            }
+            let s = &input[offset..end];
+            // a component is a number and its unit; the next number starts the next component
+            let number = number_len(s, self.separators);
+            let unit = s.len() - s[number..].trim_start().len();
+            let len = s[unit..]
+                .find(|c: char| c.is_ascii_digit() || c == '.')
+                .map_or(s.len(), |i| unit + i);
+            // only the last number may omit its unit, so "1 000" is not read as 1 + 000 bytes
+            if len == unit && len < s.len() && number > 0 {
+                let next = len + number_len(&s[len..], self.separators);
+                let span = offset..offset + next;
+                offset = end;
+                return Some(Err(ParseError::new(
+                    ParseErrorKind::InvalidNumber,
+                    input,
+                    span,
+                )));
+            }
+            // whitespace separates components, but stays part of the last one unless trimmed
+            let component = if len == s.len() {
+                s
+            } else {
+                s[..len].trim_end()
+            };
+            let parsed = self.component(input, offset..offset + component.len());
+            offset += len;
+            Some(parsed)
+        }))
+    }
+
+    /// Parses the number and unit at `span`, counting bytes in exact fixed-point arithmetic.
+    fn component(&self, input: &str, span: Range<usize>) -> Result<Component, ParseError> {
+        let value = &input[span.clone()];
+        let number = &value[..number_len(value, self.separators)];
+        let radix = radix_literal(number);
+        let valid = match radix {
+            Some((radix, digits)) => digits.chars().all(|c| c == '_' || c.is_digit(radix)),
+            None => self.is_decimal(number),
+        };
+        if !valid {
+            let end = if number.is_empty() {
+                value.len()
+            } else {
+                number.len()
+            };
+            return Err(ParseError::new(
+                ParseErrorKind::InvalidNumber,
+                input,
+                span.start..span.start + end,
+            ));
+        }
+        let suffix = value[number.len()..].trim_start();
+        let start = span.end - suffix.len();
+        let (unit, bits, ambiguous) = self.unit(input, start..span.end)?;
+
+        let overflow = || ParseError::new(ParseErrorKind::Overflow, input, span.clone());
+        let (mut amount, exact) = match radix {
+            Some((radix, digits)) => {
+                let amount = digits
+                    .chars()
+                    .filter_map(|c| c.to_digit(radix))
+                    .try_fold(0u128, |amount, digit| {
+                        amount
+                            .checked_mul(u128::from(radix))?
+                            .checked_add(u128::from(digit))
+                    })
+                    .and_then(|amount| amount.checked_mul(unit.factor()))
+                    .ok_or_else(overflow)?;
+                (amount, true)
+            }
+            // the largest `ByteSize128` as displayed overflows even a `u128`
+            None => match scale_decimal(number, unit.factor()) {
+                Some(scaled) => scaled,
+                None if !self.exact && !bits && renders_max(number, unit, u128::MAX) => {
+                    (u128::MAX, false)
+                }
+                None => return Err(overflow()),
+            },
+        };
+
+        if bits {
+            if !exact || amount % 8 != 0 {
+                return Err(ParseError::new(
+                    ParseErrorKind::FractionalBytes,
+                    input,
+                    span,
+                ));
+            }
+            amount /= 8;
+        }
+        if self.exact && !exact {
+            return Err(ParseError::new(
+                ParseErrorKind::FractionalBytes,
+                input,
+                span,
+            ));
         }
+        Ok(Component {
+            span,
+            bytes: amount,
+            unit,
+            bits,
+            ambiguous,
+        })
     }
 
-    impl ops::Add<Unit> for u64 {
-        type Output = u64;
+    /// Parses the unit suffix at `span` into the unit, whether it counts bits and whether it
+    /// was written ambiguously.
+    ///
+    /// The final letter is always case-sensitive: `b` or `bit` counts bits, `B` or `byte`
+    /// counts bytes. A bare prefix such as `K` or `Mi` counts bytes.
+    pub(crate) fn unit(
+        &self,
+        input: &str,
+        span: Range<usize>,
+    ) -> Result<(Unit, bool, bool), ParseError> {
+        let written = &input[span.clone()];
+        if written.is_empty() {
+            return Ok((self.default_unit, false, false));
+        }
+        let unknown = || {
+            let mut error = ParseError::new(ParseErrorKind::UnknownUnit, input, span.clone());
+            error.suggestion = suggest(written);
+            error
+        };
+        if !written.is_ascii() {
+            return Err(unknown());
+        }
+        let lower = written.to_ascii_lowercase();
+
+        let (prefix, bits, letter) = if let Some(prefix) = lower
+            .strip_suffix("bytes")
+            .or_else(|| lower.strip_suffix("byte"))
+        {
+            (prefix, false, false)
+        } else if let Some(prefix) = lower
+            .strip_suffix("bits")
+            .or_else(|| lower.strip_suffix("bit"))
+        {
+            (prefix, true, false)
+        } else if written.ends_with('B') {
+            (&lower[..lower.len() - 1], false, true)
+        } else if written.ends_with('b') {
+            (&lower[..lower.len() - 1], true, true)
+        } else {
+            (lower.as_str(), false, false)
+        };
 
-        fn add(self, other: Unit) -> Self::Output {
-            self + other.factor()
+        let unit = match PREFIXES.iter().find(|&&(name, _)| name == prefix) {
+            Some(&(_, unit)) => unit,
+            None => return Err(unknown()),
+        };
+
+        // symbol prefixes are one or two letters, spelled-out names are longer
+        let symbol = prefix.len() <= 2;
+        let miscased = symbol
+            && match written.as_bytes()[..prefix.len()] {
+                [] => false,
+                [p] => !(p.is_ascii_uppercase() || p == b'k'),
+                [p, i] => !(p.is_ascii_uppercase() && i == b'i'),
+                _ => unreachable!("symbol prefixes are at most two letters"),
+            };
+        if miscased && self.case_sensitive {
+            return Err(unknown());
         }
+
+        let decimal = unit.system() == UnitSystem::Decimal;
+        let bare = !prefix.is_empty() && prefix.len() == lower.len();
+        let ambiguous = miscased
+            || bare && decimal
+            || letter && bits && !written.bytes().any(|c| c.is_ascii_uppercase());
+        if ambiguous && self.strict {
+            let mut error = ParseError::new(ParseErrorKind::AmbiguousUnit, input, span);
+            error.suggestion = suggest(written);
+            return Err(error);
+        }
+
+        let unit = match self.system {
+            UnitSystem::Binary | UnitSystem::Jedec if symbol && decimal => {
+                Unit::from_exponent(UnitSystem::Binary, unit.exponent())
+            }
+            _ => unit,
+        };
+        Ok((unit, bits, ambiguous))
+    }
+}
+
+impl ParseOptions {
+    /// Whether `number` is a well-formed decimal number, with separators only between
+    /// thousands of its integer part.
+    pub(crate) fn is_decimal(&self, number: &str) -> bool {
+        let mantissa = match number.find(['e', 'E']) {
+            Some(i) => &number[..i],
+            None => number,
+        };
+        let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        let mut groups = integer.split(|c| self.separators.contains(&c));
+        let grouped = groups
+            .next()
+            .map_or(false, |first| (1..=3).contains(&first.len()))
+            && groups.all(|group| group.len() == 3);
+        !(integer.is_empty() && fraction.is_empty())
+            && (grouped || !integer.contains(|c| self.separators.contains(&c)))
+            && fraction.bytes().all(|c| c.is_ascii_digit())
     }
+}
+
+/// Whether the decimal `number` of `unit` is how [`Display`](crate::Display) renders `max`
+/// bytes in that unit, such as `"16.0"` EiB for `u64::MAX`.
+fn renders_max(number: &str, unit: Unit, max: u128) -> bool {
+    let rendered = Display::new(max).unit(unit).to_string();
+    rendered.split(' ').next() == Some(number)
+}
 
-    impl ops::Mul<u64> for Unit {
-        type Output = u64;
+/// Multiplies the decimal `number` by `factor`, returning the product rounded down and
+/// whether it is exact, or `None` on overflow.
+fn scale_decimal(number: &str, factor: u128) -> Option<(u128, bool)> {
+    let (mantissa, exponent) = match number.find(['e', 'E']) {
+        Some(i) => (&number[..i], &number[i + 1..]),
+        None => (number, ""),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
 
-        fn mul(self, other: u64) -> Self::Output {
-            self.factor() * other
+    // anything beyond this many places either overflows or vanishes below one byte
+    const EXPONENT_LIMIT: i64 = 1_000;
+    let exponent = match exponent.strip_prefix('-') {
+        Some(digits) => -digits_value(digits).min(EXPONENT_LIMIT),
+        None => digits_value(exponent.trim_start_matches('+')).min(EXPONENT_LIMIT),
+    };
+
+    // the number is its digits with the decimal point moved by the exponent
+    let digits = || {
+        integer
+            .bytes()
+            .chain(fraction.bytes())
+            .filter(u8::is_ascii_digit)
+            .map(|c| u128::from(c - b'0'))
+    };
+    let count = digits().count() as i64;
+    let point = count - fraction.len() as i64 + exponent;
+
+    let mut whole: u128 = 0;
+    for digit in digits().take(point.clamp(0, count) as usize) {
+        whole = whole.checked_mul(10)?.checked_add(digit)?;
+    }
+    if whole != 0 {
+        for _ in count..point {
+            whole = whole.checked_mul(10)?;
         }
     }
+    // Horner's scheme from the last digit: floor((d + floor(x)) / 10) == floor((d + x) / 10),
+    // so truncating at every step still yields the exactly truncated fraction
+    let mut part: u128 = 0;
+    let mut exact = true;
+    let leading_zeros = (-point).clamp(0, 64) as usize;
+    let fraction_digits = digits()
+        .rev()
+        .take((count - point.clamp(0, count)) as usize)
+        .chain(std::iter::repeat(0).take(leading_zeros));
+    for digit in fraction_digits {
+        let n = digit * factor + part;
+        exact &= n % 10 == 0;
+        part = n / 10;
+    }
+    Some((whole.checked_mul(factor)?.checked_add(part)?, exact))
+}
 
-    impl ops::Mul<Unit> for u64 {
-        type Output = u64;
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions::new()
+    }
+}
+
+impl std::str::FromStr for ByteSize {
+    type Err = ParseError;
 
-        fn mul(self, other: Unit) -> Self::Output {
-            self * other.factor()
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if let Ok(v) = value.parse::<u64>() {
+            return Ok(Self(v));
         }
+        Ok(ParseOptions::new().parse(value)?.size)
     }
+}
 
-    impl ops::Add<f64> for Unit {
-        type Output = f64;
+impl std::str::FromStr for ByteSize128 {
+    type Err = ParseError;
 
-        fn add(self, other: f64) -> Self::Output {
-            self.factor() as f64 + other
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if let Ok(v) = value.parse::<u128>() {
+            return Ok(Self(v));
         }
+        Ok(ParseOptions::new().parse128(value)?.size)
     }
+}
 
-    impl ops::Add<Unit> for f64 {
-        type Output = f64;
+/// Parses a size with an optional leading `+` or `-` sign, e.g. `"-512 MiB"`.
+impl std::str::FromStr for ByteSizeDelta {
+    type Err = ParseError;
 
-        fn add(self, other: Unit) -> Self::Output {
-            other.factor() as f64 + self
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if let Ok(v) = value.parse::<i64>() {
+            return Ok(Self(v));
         }
+
+        let start = value.len() - value.trim_start().len();
+        let (negative, offset) = match value[start..].chars().next() {
+            Some('-') => (true, start + 1),
+            Some('+') => (false, start + 1),
+            _ => (false, start),
+        };
+        // the sign belongs to the number, so `"- 2 KB"` is no delta
+        let detached = value[offset..]
+            .chars()
+            .next()
+            .map_or(true, char::is_whitespace);
+        if offset > start && detached {
+            return Err(ParseError::new(
+                ParseErrorKind::InvalidNumber,
+                value,
+                start..offset,
+            ));
+        }
+
+        // the most negative delta has no positive counterpart in an `i64`
+        let max = if negative { 1 << 63 } else { i64::MAX as u128 };
+        // unlike `ByteSize::from_str`, this has no integer fast path that accepts a second sign
+        let magnitude = ParseOptions::new()
+            .parse_bounded(&value[offset..], max)
+            .map_err(|e| e.offset(offset))?
+            .size;
+        Ok(ByteSizeDelta(if negative {
+            (magnitude as i64).wrapping_neg()
+        } else {
+            magnitude as i64
+        }))
     }
+}
 
-    impl ops::Mul<f64> for Unit {
-        type Output = f64;
+impl ByteSize {
+    /// Parses a size like [`FromStr`](std::str::FromStr), but fails instead of rounding down
+    /// when it is not a whole number of bytes.
+    ///
+    /// ```
+    /// use bytesize::{ByteSize, ParseErrorKind};
+    ///
+    /// assert_eq!(ByteSize::parse_exact("1.5 KiB"), Ok(ByteSize::b(1536)));
+    /// let error = ByteSize::parse_exact("0.1 KiB").unwrap_err();
+    /// assert_eq!(error.kind(), ParseErrorKind::FractionalBytes);
+    /// ```
+    pub fn parse_exact(value: &str) -> Result<ByteSize, ParseError> {
+        Ok(ParseOptions::new().exact(true).parse(value)?.size)
+    }
+}
+
+impl ByteSize128 {
+    /// Parses a size like [`FromStr`](std::str::FromStr), but fails instead of rounding down
+    /// when it is not a whole number of bytes.
+    pub fn parse_exact(value: &str) -> Result<ByteSize128, ParseError> {
+        Ok(ParseOptions::new().exact(true).parse128(value)?.size)
+    }
+}
+
+/// Length of the number at the start of `s`: digits, decimal points and digit separators
+/// followed by a digit, then an optional exponent such as `e9` or `E-3`. An `e` without
+/// digits after it is left alone, as it may be the exabyte unit.
+pub(crate) fn number_len(s: &str, separators: &[char]) -> usize {
+    if let Some((radix, digits)) = radix_literal(s) {
+        let mut len = 2;
+        let mut chars = digits.chars().peekable();
+        while let Some(c) = chars.next() {
+            let digit_follows = chars.peek().map_or(false, |c| c.is_digit(radix));
+            if !(c.is_digit(radix) || c == '_' && digit_follows) {
+                break;
+            }
+            len += 1;
+        }
+        // keep a decimal point, so that `0x1.5` is rejected rather than read as `0x1` + `.5`
+        if s[len..].starts_with('.') {
+            len += 1;
+        }
+        return len;
+    }
 
-        fn mul(self, other: f64) -> Self::Output {
-            self.factor() as f64 * other
+    let mut len = 0;
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        let digit_follows = chars.peek().map_or(false, char::is_ascii_digit);
+        if !(c.is_ascii_digit() || c == '.' || separators.contains(&c) && digit_follows) {
+            break;
         }
+        len += c.len_utf8();
+    }
+    if !s[..len].contains(|c: char| c.is_ascii_digit()) {
+        return len;
     }
 
-    impl ops::Mul<Unit> for f64 {
-        type Output = f64;
+    let exponent = match s[len..].strip_prefix(['e', 'E']) {
+        Some(exponent) => exponent,
+        None => return len,
+    };
+    let digits = exponent.trim_start_matches(['+', '-']);
+    let sign = exponent.len() - digits.len();
+    let count = digits.bytes().take_while(u8::is_ascii_digit).count();
+    if sign > 1 || count == 0 {
+        return len;
+    }
+    len + 1 + sign + count
+}
+
+/// The radix and digits of an integer literal prefixed with `0x`, `0o` or `0b`. Hex digits
+/// run on greedily, so `0x10B` is 267 bytes while `0x10 B` is 16.
+pub(crate) fn radix_literal(number: &str) -> Option<(u32, &str)> {
+    let radix = match number.get(..2)? {
+        "0x" => 16,
+        "0o" => 8,
+        "0b" => 2,
+        _ => return None,
+    };
+    let digits = &number[2..];
+    digits
+        .starts_with(|c: char| c.is_digit(radix))
+        .then_some((radix, digits))
+}
+
+/// Value of a string of ASCII digits, saturating instead of overflowing.
+pub(crate) fn digits_value(digits: &str) -> i64 {
+    digits.bytes().fold(0, |value: i64, c| {
+        value.saturating_mul(10).saturating_add(i64::from(c - b'0'))
+    })
+}
+
+/// Spellings offered as suggestions for unknown units.
+static SUGGESTIONS: &[&str] = &[
+    "B",
+    "KB",
+    "MB",
+    "GB",
+    "TB",
+    "PB",
+    "EB",
+    "ZB",
+    "YB",
+    "RB",
+    "QB",
+    "KiB",
+    "MiB",
+    "GiB",
+    "TiB",
+    "PiB",
+    "EiB",
+    "ZiB",
+    "YiB",
+    "RiB",
+    "QiB",
+    "bit",
+    "kbit",
+    "Mbit",
+    "Gbit",
+    "Tbit",
+    "Kibit",
+    "Mibit",
+    "Gibit",
+    "Tibit",
+    "bytes",
+    "kilobytes",
+    "megabytes",
+    "gigabytes",
+    "terabytes",
+    "petabytes",
+    "exabytes",
+    "kibibytes",
+    "mebibytes",
+    "gibibytes",
+    "tebibytes",
+    "pebibytes",
+    "exbibytes",
+];
 
-        fn mul(self, other: Unit) -> Self::Output {
-            other.factor() as f64 * self
+/// The known spelling closest to `unit`, if it is only a typo or two away.
+fn suggest(unit: &str) -> Option<&'static str> {
+    let unit = unit.to_lowercase();
+    SUGGESTIONS
+        .iter()
+        .map(|&candidate| (edit_distance(&unit, &candidate.to_lowercase()), candidate))
+        .filter(|&(distance, candidate)| distance <= 2 && distance < candidate.len())
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance between two strings, counted in chars.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
         }
     }
+    row[b.len()]
 }
 
 impl std::str::FromStr for Unit {
-    type Err = String;
+    type Err = ParseError;
 
     fn from_str(unit: &str) -> Result<Self, Self::Err> {
-        match unit.to_lowercase().as_str() {
-            "b" => Ok(Self::Byte),
-            // power of tens
-            "k" | "kb" => Ok(Self::KiloByte),
-            "m" | "mb" => Ok(Self::MegaByte),
-            "g" | "gb" => Ok(Self::GigaByte),
-            "t" | "tb" => Ok(Self::TeraByte),
-            "p" | "pb" => Ok(Self::PetaByte),
-            // power of twos
-            "ki" | "kib" => Ok(Self::KibiByte),
-            "mi" | "mib" => Ok(Self::MebiByte),
-            "gi" | "gib" => Ok(Self::GibiByte),
-            "ti" | "tib" => Ok(Self::TebiByte),
-            "pi" | "pib" => Ok(Self::PebiByte),
-            _ => Err(format!("couldn't parse unit of {:?}", unit)),
+        // `unit()` reads a missing unit as the default one
+        if unit.is_empty() {
+            return Err(ParseError::new(ParseErrorKind::Empty, unit, 0..0));
+        }
+        match ParseOptions::new().unit(unit, 0..unit.len())? {
+            (parsed, false, _) => Ok(parsed),
+            (_, true, _) => Err(ParseError::new(
+                ParseErrorKind::BitUnit,
+                unit,
+                0..unit.len(),
+            )),
         }
     }
 }
@@ -188,33 +909,151 @@ mod tests {
     #[test]
     fn when_ok() {
         // shortcut for writing test cases
-        fn parse(s: &str) -> u64 {
-            s.parse::<ByteSize>().unwrap().0
+        fn parse(s: &str) -> u128 {
+            s.parse::<ByteSize>().unwrap().0.into()
         }
 
         assert_eq!("0".parse::<ByteSize>().unwrap().0, 0);
         assert_eq!(parse("0"), 0);
         assert_eq!(parse("500"), 500);
-        assert_eq!(parse("1K"), Unit::KiloByte * 1);
-        assert_eq!(parse("1Ki"), Unit::KibiByte * 1);
-        assert_eq!(parse("1.5Ki"), (1.5 * Unit::KibiByte) as u64);
-        assert_eq!(parse("1KiB"), 1 * Unit::KibiByte);
-        assert_eq!(parse("1.5KiB"), (1.5 * Unit::KibiByte) as u64);
-        assert_eq!(parse("3 MB"), Unit::MegaByte * 3);
-        assert_eq!(parse("4 MiB"), Unit::MebiByte * 4);
-        assert_eq!(parse("6 GB"), 6 * Unit::GigaByte);
-        assert_eq!(parse("4 GiB"), 4 * Unit::GibiByte);
-        assert_eq!(parse("88TB"), 88 * Unit::TeraByte);
-        assert_eq!(parse("521TiB"), 521 * Unit::TebiByte);
-        assert_eq!(parse("8 PB"), 8 * Unit::PetaByte);
-        assert_eq!(parse("8P"), 8 * Unit::PetaByte);
-        assert_eq!(parse("12 PiB"), 12 * Unit::PebiByte);
+        assert_eq!(parse("1K"), Unit::KB.factor());
+        assert_eq!(parse("1Ki"), Unit::KiB.factor());
+        assert_eq!(parse("1.5Ki"), (1.5 * Unit::KiB.factor() as f64) as u128);
+        assert_eq!(parse("1KiB"), Unit::KiB.factor());
+        assert_eq!(parse("1.5KiB"), (1.5 * Unit::KiB.factor() as f64) as u128);
+        assert_eq!(parse("3 MB"), 3 * Unit::MB.factor());
+        assert_eq!(parse("4 MiB"), 4 * Unit::MiB.factor());
+        assert_eq!(parse("6 GB"), 6 * Unit::GB.factor());
+        assert_eq!(parse("4 GiB"), 4 * Unit::GiB.factor());
+        assert_eq!(parse("88TB"), 88 * Unit::TB.factor());
+        assert_eq!(parse("521TiB"), 521 * Unit::TiB.factor());
+        assert_eq!(parse("8 PB"), 8 * Unit::PB.factor());
+        assert_eq!(parse("8P"), 8 * Unit::PB.factor());
+        assert_eq!(parse("12 PiB"), 12 * Unit::PiB.factor());
+        assert_eq!(parse("3 EB"), 3 * Unit::EB.factor());
+        assert_eq!(parse("3E"), 3 * Unit::EB.factor());
+        assert_eq!(parse("15 EiB"), 15 * Unit::EiB.factor());
+        assert_eq!(parse("1.5 exbibytes"), 3 * Unit::EiB.factor() / 2);
+    }
+
+    #[test]
+    fn long_names() {
+        // shortcut for writing test cases
+        fn parse(s: &str) -> u128 {
+            s.parse::<ByteSize>().unwrap().0.into()
+        }
+
+        assert_eq!(parse("1 byte"), 1);
+        assert_eq!(parse("2 bytes"), 2);
+        assert_eq!(parse("1 kilobyte"), Unit::KB.factor());
+        assert_eq!(parse("3 megabytes"), 3 * Unit::MB.factor());
+        assert_eq!(parse("3 MiBytes"), 3 * Unit::MiB.factor());
+        assert_eq!(parse("2 kbytes"), 2 * Unit::KB.factor());
+        assert_eq!(parse("1.5 Gibibytes"), 3 * Unit::MiB.factor() * 512);
+        assert_eq!(parse("7 pebibyte"), 7 * Unit::PiB.factor());
+
+        assert!("1 xbytes".parse::<ByteSize>().is_err());
+    }
+
+    #[test]
+    fn bits() {
+        // shortcut for writing test cases
+        fn parse(s: &str) -> u128 {
+            s.parse::<ByteSize>().unwrap().0.into()
+        }
+
+        assert_eq!(parse("8 b"), 1);
+        assert_eq!(parse("16 bit"), 2);
+        assert_eq!(parse("24 bits"), 3);
+        assert_eq!(parse("1 Gb"), Unit::GB.factor() / 8);
+        assert_eq!(parse("1 GB"), Unit::GB.factor());
+        assert_eq!(parse("8 kb"), Unit::KB.factor());
+        assert_eq!(parse("8 kbit"), Unit::KB.factor());
+        assert_eq!(parse("8 Kibit"), Unit::KiB.factor());
+        assert_eq!(parse("8 Mibit"), Unit::MiB.factor());
+        assert_eq!(parse("8 Mib"), Unit::MiB.factor());
+        assert_eq!(parse("8 MiB"), 8 * Unit::MiB.factor());
+        assert_eq!(parse("1 kilobits"), 125);
+        assert_eq!(parse("4 gibibits"), Unit::GiB.factor() / 2);
+        assert_eq!(parse("1.5 Mbit"), 187_500);
+
+        // bit counts must fill whole bytes
+        assert!("12 bit".parse::<ByteSize>().is_err());
+        assert!("1 b".parse::<ByteSize>().is_err());
+        assert!("0.1 Kibit".parse::<ByteSize>().is_err());
+        assert!("12 bit".parse::<ByteSize128>().is_err());
+        assert_eq!("8 Yb".parse::<ByteSize128>().unwrap().0, Unit::YB.factor());
+
+        // units themselves are always bytes
+        assert_eq!("MB".parse::<Unit>(), Ok(Unit::MB));
+        assert!("Mb".parse::<Unit>().is_err());
+        assert!("kbit".parse::<Unit>().is_err());
+    }
+
+    #[test]
+    fn concatenated() {
+        // shortcut for writing test cases
+        fn parse(s: &str) -> u128 {
+            s.parse::<ByteSize>().unwrap().0.into()
+        }
+
+        let expected = Unit::GiB.factor() + 512 * Unit::MiB.factor();
+        assert_eq!(parse("1GiB 512MiB"), expected);
+        assert_eq!(parse("1 GiB 512 MiB"), expected);
+        assert_eq!(parse("1G512M"), Unit::GB.factor() + 512 * Unit::MB.factor());
+        assert_eq!(parse("1Gi512Mi"), expected);
+        assert_eq!(parse("1 KiB 1"), 1025);
+        assert_eq!(parse("1 kilobyte 5 bytes"), 1005);
+        assert_eq!(parse("1.5 KiB 1 B"), 1537);
+        assert_eq!(
+            "1 ZiB 1 B".parse::<ByteSize128>().unwrap().0,
+            Unit::ZiB.factor() + 1
+        );
+
+        assert!("1 GiB xyz".parse::<ByteSize>().is_err());
+        assert!("1 GiB 2 xyz".parse::<ByteSize>().is_err());
+
+        // a number without a unit ends the size
+        for (input, span) in [
+            ("1 000 000", 0..5),
+            ("1 2", 0..3),
+            ("5 5", 0..3),
+            ("1 KiB 2 3 KiB", 6..9),
+            ("1 .5", 0..4),
+        ] {
+            let e = input.parse::<ByteSize>().unwrap_err();
+            assert_eq!((e.kind(), e.span()), (ParseErrorKind::InvalidNumber, span));
+        }
+        assert_eq!(
+            "1 000".parse::<ByteSize>().unwrap_err().to_string(),
+            "invalid number \"1 000\""
+        );
+    }
+
+    #[test]
+    fn byte_size_128() {
+        // shortcut for writing test cases
+        fn parse(s: &str) -> u128 {
+            s.parse::<ByteSize128>().unwrap().0
+        }
+
+        assert_eq!(parse("0"), 0);
+        assert_eq!(parse("340282366920938463463374607431768211455"), u128::MAX);
+        assert_eq!(parse("5 ZB"), 5 * Unit::ZB.factor());
+        assert_eq!(parse("5 ZiB"), 5 * Unit::ZiB.factor());
+        assert_eq!(parse("2Y"), 2 * Unit::YB.factor());
+        assert_eq!(parse("2 yobibytes"), 2 * Unit::YiB.factor());
+        assert_eq!(parse("7 RB"), 7 * Unit::RB.factor());
+        assert_eq!(parse("7 RiB"), 7 * Unit::RiB.factor());
+        assert_eq!(parse("9 QB"), 9 * Unit::QB.factor());
+        assert_eq!(parse("9 quebibytes"), 9 * Unit::QiB.factor());
+        assert_eq!(parse("4 GiB"), 4 * Unit::GiB.factor());
     }
 
     #[test]
     fn when_err() {
         // shortcut for writing test cases
-        fn parse(s: &str) -> Result<ByteSize, String> {
+        fn parse(s: &str) -> Result<ByteSize, ParseError> {
             s.parse::<ByteSize>()
         }
 
@@ -222,17 +1061,441 @@ mod tests {
         assert!(parse("a124GB").is_err());
     }
 
+    #[test]
+    fn errors() {
+        // shortcut for writing test cases
+        fn error(s: &str) -> ParseError {
+            s.parse::<ByteSize>().unwrap_err()
+        }
+
+        let e = error("");
+        assert_eq!((e.kind(), e.span()), (ParseErrorKind::Empty, 0..0));
+        assert_eq!(error("  ").kind(), ParseErrorKind::Empty);
+
+        let e = error("1.2.3 KiB");
+        assert_eq!((e.kind(), e.span()), (ParseErrorKind::InvalidNumber, 0..5));
+        assert_eq!(e.to_string(), "invalid number \"1.2.3\"");
+        let e = error("a124GB");
+        assert_eq!((e.kind(), e.span()), (ParseErrorKind::InvalidNumber, 0..1));
+
+        let e = error("1 GiB 2 KiBB");
+        assert_eq!((e.kind(), e.span()), (ParseErrorKind::UnknownUnit, 8..12));
+        assert_eq!(e.suggestion(), Some("KiB"));
+        assert_eq!(error("3 megabites").suggestion(), Some("megabytes"));
+        assert_eq!(error("3 xyzzy").suggestion(), None);
+        assert_eq!(error("3 xyzzy").to_string(), "unknown unit \"xyzzy\"");
+
+        let e = error(" 12 bit");
+        assert_eq!(
+            (e.kind(), e.span()),
+            (ParseErrorKind::FractionalBytes, 1..7)
+        );
+        assert_eq!(e.to_string(), "\"12 bit\" is not a whole number of bytes");
+
+        assert_eq!(error("17 EiB").kind(), ParseErrorKind::Overflow);
+        assert_eq!(
+            error("99999999999999999999").kind(),
+            ParseErrorKind::Overflow
+        );
+        assert_eq!(error("15 EiB 2 EiB").kind(), ParseErrorKind::Overflow);
+        assert_eq!(
+            "400000000 QB".parse::<ByteSize128>().unwrap_err().kind(),
+            ParseErrorKind::Overflow
+        );
+
+        let e = "".parse::<Unit>().unwrap_err();
+        assert_eq!((e.kind(), e.span()), (ParseErrorKind::Empty, 0..0));
+        let e = "Mb".parse::<Unit>().unwrap_err();
+        assert_eq!((e.kind(), e.span()), (ParseErrorKind::BitUnit, 0..2));
+        assert_eq!(
+            "MIBs".parse::<Unit>().unwrap_err().suggestion(),
+            Some("MiB")
+        );
+    }
+
+    #[test]
+    fn exact() {
+        // shortcut for writing test cases
+        fn parse(s: &str) -> u128 {
+            s.parse::<ByteSize>().unwrap().0.into()
+        }
+
+        // beyond the 53 bits `f64` holds exactly
+        assert_eq!(parse("9007199254740993 B"), 9_007_199_254_740_993);
+        assert_eq!(parse("9007199254740993.5 B"), 9_007_199_254_740_993);
+        assert_eq!(parse("16777217.1 KiB"), 16_777_217 * 1024 + 102);
+        assert_eq!(parse("0.1 KiB"), 102);
+        assert_eq!(parse("0.999 KB"), 999);
+        assert_eq!(parse(".5 KiB"), 512);
+        assert_eq!(parse("2. KiB"), 2048);
+        assert_eq!(
+            parse("1.000000000000000000000000000000000000000001 KiB"),
+            1024
+        );
+        assert_eq!(parse("15.99999999999999999999 EiB"), u64::MAX.into());
+        assert_eq!(parse("18446744073709551615 B"), u64::MAX.into());
+        assert_eq!(
+            "1.5 QB".parse::<ByteSize128>().unwrap().0,
+            15 * Unit::QB.factor() / 10
+        );
+
+        // the largest size as rendered is read as the largest size, but nothing else past it
+        assert_eq!(parse("16.0 EiB"), u64::MAX.into());
+        assert_eq!(parse(&ByteSize(u64::MAX).to_string()), u64::MAX.into());
+        let max = ByteSize128(u128::MAX);
+        assert_eq!(max.to_string().parse(), Ok(max));
+        for input in ["268435456.1 QiB", "268435456.00 QiB", "268435456.0 QiB 1 B"] {
+            let e = input.parse::<ByteSize128>().unwrap_err();
+            assert_eq!(e.kind(), ParseErrorKind::Overflow, "{}", input);
+        }
+        let e = ByteSize128::parse_exact("268435456.0 QiB").unwrap_err();
+        assert_eq!(e.kind(), ParseErrorKind::Overflow);
+        let inputs = [
+            "16.1 EiB",
+            "16.00 EiB",
+            "18.45 EB",
+            "0.02e3 EiB",
+            "1.6e1 EiB",
+            "16.0 EiB 0 B",
+            "15 EiB 1.0 EiB",
+        ];
+        for input in inputs {
+            let e = input.parse::<ByteSize>().unwrap_err();
+            assert_eq!(e.kind(), ParseErrorKind::Overflow, "{}", input);
+        }
+        let e = ByteSize::parse_exact("16.0 EiB").unwrap_err();
+        assert_eq!(e.kind(), ParseErrorKind::Overflow);
+
+        for input in ["99999999 PiB", "16 EiB", "18446744073709551616 B"] {
+            let e = input.parse::<ByteSize>().unwrap_err();
+            assert_eq!(e.kind(), ParseErrorKind::Overflow, "{}", input);
+        }
+        assert_eq!(
+            "99999999999999999999999999999999999999999 B"
+                .parse::<ByteSize128>()
+                .unwrap_err()
+                .kind(),
+            ParseErrorKind::Overflow
+        );
+        for input in [".", "1..5 KiB", "1.2.3"] {
+            let e = input.parse::<ByteSize>().unwrap_err();
+            assert_eq!(e.kind(), ParseErrorKind::InvalidNumber, "{}", input);
+        }
+
+        assert_eq!(ByteSize::parse_exact("1.5 KiB"), Ok(ByteSize::b(1536)));
+        assert_eq!(
+            ByteSize::parse_exact("1 GiB 0.5 KiB"),
+            Ok(ByteSize::b(1 << 30 | 512))
+        );
+        let e = ByteSize::parse_exact("1 GiB 0.1 KiB").unwrap_err();
+        assert_eq!(
+            (e.kind(), e.span()),
+            (ParseErrorKind::FractionalBytes, 6..13)
+        );
+        assert!(ByteSize::parse_exact("9007199254740993.5 B").is_err());
+        assert!(ByteSize128::parse_exact("0.0000001 QB").is_ok());
+        assert!(ByteSize128::parse_exact("0.1 QiB").is_err());
+    }
+
+    #[test]
+    fn number_grammar() {
+        // shortcut for writing test cases
+        fn parse(s: &str) -> u128 {
+            s.parse::<ByteSize>().unwrap().0.into()
+        }
+
+        assert_eq!(parse("1e9"), 1_000_000_000);
+        assert_eq!(parse("1E9"), 1_000_000_000);
+        assert_eq!(parse("1e+9 B"), 1_000_000_000);
+        assert_eq!(parse("1.5E3 KB"), 1_500_000);
+        assert_eq!(parse("2.5e-3 MB"), 2_500);
+        assert_eq!(parse("1e-3"), 0);
+        assert_eq!(parse("0e999999999"), 0);
+        assert_eq!(parse("12345678901234567e-1"), 1_234_567_890_123_456);
+        assert_eq!(parse("9.007199254740993e15"), 9_007_199_254_740_993);
+        assert_eq!(parse("1e1GiB"), 10 * Unit::GiB.factor());
+        // an `e` without exponent digits is still the exabyte unit
+        assert_eq!(parse("1E"), Unit::EB.factor());
+        assert_eq!(parse("1EiB"), Unit::EiB.factor());
+        assert_eq!(parse("1e 5"), Unit::EB.factor() + 5);
+
+        assert_eq!(parse("1_000_000"), 1_000_000);
+        assert_eq!(parse("1,048,576 B"), 1_048_576);
+        assert_eq!(parse("1\u{2009}048\u{2009}576 B"), 1_048_576);
+        assert_eq!(parse("1,536.5 KiB"), 1536 * 1024 + 512);
+        assert_eq!(parse("1,000 B 24 B"), 1024);
+        assert_eq!(parse("5\u{2009}KiB"), 5 * 1024);
+
+        for input in ["1e400", "1e20 EB"] {
+            let e = input.parse::<ByteSize>().unwrap_err();
+            assert_eq!(e.kind(), ParseErrorKind::Overflow, "{}", input);
+        }
+        for input in [
+            "1,5 GiB", "1_0000", ",000", "1234,567", "1.000,5", "1,000,00",
+        ] {
+            let e = input.parse::<ByteSize>().unwrap_err();
+            assert_eq!(e.kind(), ParseErrorKind::InvalidNumber, "{}", input);
+        }
+        assert_eq!(
+            ByteSize::parse_exact("1e-3").unwrap_err().kind(),
+            ParseErrorKind::FractionalBytes
+        );
+        assert!(ByteSize::parse_exact("1.5e-4 KB").is_err());
+        assert_eq!(ByteSize::parse_exact("1.5e-1 MB"), Ok(ByteSize::kb(150)));
+
+        let underscores = ParseOptions::new().digit_separators(&['_']);
+        assert_eq!(underscores.parse("1_000").unwrap().size, ByteSize::kb(1));
+        assert!(underscores.parse("1,000").is_err());
+        assert!(ParseOptions::new()
+            .digit_separators(&[])
+            .parse("1_000")
+            .is_err());
+        let apostrophes = ParseOptions::new().digit_separators(&['\'']);
+        assert_eq!(
+            apostrophes.parse("1'048'576 B").unwrap().size,
+            ByteSize::mib(1)
+        );
+    }
+
+    #[test]
+    fn digit_separators_in_numbers() {
+        let separators: [&'static [char]; 7] =
+            [&['.'], &['0'], &['e'], &['E'], &['+'], &['-'], &['_', 'x']];
+        for separators in separators {
+            let result =
+                std::panic::catch_unwind(|| ParseOptions::new().digit_separators(separators));
+            assert!(result.is_err(), "{:?}", separators);
+        }
+    }
+
+    #[test]
+    fn radix_literals() {
+        // shortcut for writing test cases
+        fn parse(s: &str) -> u128 {
+            s.parse::<ByteSize>().unwrap().0.into()
+        }
+
+        assert_eq!(parse("0x1000"), 4096);
+        assert_eq!(parse("0xFFFF_FFFF"), 0xFFFF_FFFF);
+        assert_eq!(parse("0o755"), 0o755);
+        assert_eq!(parse("0b1_0000_0000"), 256);
+        assert_eq!(parse("0x10 MiB"), 16 * Unit::MiB.factor());
+        assert_eq!(parse("0x10MiB"), 16 * Unit::MiB.factor());
+        assert_eq!(parse("0x10 B"), 16);
+        // hex digits run on greedily
+        assert_eq!(parse("0x10B"), 0x10B);
+        assert_eq!(parse("0x1 GiB 0x800 MiB"), 3 * Unit::GiB.factor());
+        assert_eq!(parse("0x100 b"), 32);
+        // a `0b` or `0B` without binary digits is zero bits or bytes
+        assert_eq!(parse("0b"), 0);
+        assert_eq!(parse("0B"), 0);
+        assert_eq!(
+            "0xFFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF"
+                .parse::<ByteSize128>()
+                .unwrap()
+                .0,
+            u128::MAX
+        );
+
+        assert_eq!(
+            "0x1_0000_0000_0000_0000"
+                .parse::<ByteSize>()
+                .unwrap_err()
+                .kind(),
+            ParseErrorKind::Overflow
+        );
+        assert_eq!(
+            "0x1 QiB".parse::<ByteSize128>().unwrap().0,
+            Unit::QiB.factor()
+        );
+        assert_eq!(
+            "0x1000000000 QiB"
+                .parse::<ByteSize128>()
+                .unwrap_err()
+                .kind(),
+            ParseErrorKind::Overflow
+        );
+        for input in ["0x", "0xG", "0o8", "0x1.5 KiB", "0x_1"] {
+            assert!(input.parse::<ByteSize>().is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn options() {
+        let lenient = ParseOptions::new();
+        let parsed = lenient.parse("3 MiB").unwrap();
+        assert_eq!(parsed.size.0 as u128, 3 * Unit::MiB.factor());
+        assert_eq!(
+            (parsed.unit, parsed.bits, parsed.ambiguous),
+            (Unit::MiB, false, false)
+        );
+
+        // ambiguous spellings are accepted, but reported
+        for (input, unit, bits) in [
+            ("4K", Unit::KB, false),
+            ("4 m", Unit::MB, false),
+            ("4 mb", Unit::MB, true),
+            ("4 kb", Unit::KB, true),
+            ("4 kiB", Unit::KiB, false),
+            ("4 MIB", Unit::MiB, false),
+            ("1 GiB 4 K", Unit::KB, false),
+        ] {
+            let parsed = lenient.parse(input).unwrap();
+            assert_eq!(
+                (parsed.unit, parsed.bits, parsed.ambiguous),
+                (unit, bits, true)
+            );
+        }
+        for input in [
+            "4",
+            "4 B",
+            "4 kB",
+            "4 KB",
+            "4 Ki",
+            "4 KiB",
+            "4 Kb",
+            "4 kbit",
+            "4 kilobytes",
+        ] {
+            assert!(!lenient.parse(input).unwrap().ambiguous, "{}", input);
+        }
+
+        let strict = ParseOptions::new().strict(true);
+        let e = strict.parse("1 GiB 4K").unwrap_err();
+        assert_eq!((e.kind(), e.span()), (ParseErrorKind::AmbiguousUnit, 7..8));
+        assert_eq!(e.to_string(), "ambiguous unit \"K\", did you mean \"KB\"?");
+        assert!(strict.parse("4 kb").is_err());
+        assert!(strict.parse("4 m").is_err());
+        assert_eq!(strict.parse("4 KiB").unwrap().size, ByteSize::kib(4));
+        assert_eq!(strict.parse("4 kB").unwrap().size, ByteSize::kb(4));
+
+        let sensitive = ParseOptions::new().case_sensitive(true);
+        assert_eq!(sensitive.parse("4 MiB").unwrap().size, ByteSize::mib(4));
+        assert_eq!(
+            sensitive.parse("4 mebibytes").unwrap().size,
+            ByteSize::mib(4)
+        );
+        let e = sensitive.parse("4 MIB").unwrap_err();
+        assert_eq!(
+            (e.kind(), e.suggestion()),
+            (ParseErrorKind::UnknownUnit, Some("MiB"))
+        );
+        // unlike strict mode, bare prefixes are still fine
+        assert_eq!(sensitive.parse("4K").unwrap().size, ByteSize::kb(4));
+
+        let megabytes = ParseOptions::new().default_unit(Unit::MB);
+        assert_eq!(megabytes.parse("4").unwrap().size, ByteSize::mb(4));
+        assert_eq!(megabytes.parse("4").unwrap().unit, Unit::MB);
+        assert_eq!(megabytes.parse("4 B").unwrap().size, ByteSize::b(4));
+        assert_eq!(megabytes.parse("1.5").unwrap().size, ByteSize::kb(1500));
+
+        let untrimmed = ParseOptions::new().trim(false);
+        assert_eq!(
+            untrimmed.parse(" 4 KiB").unwrap_err().kind(),
+            ParseErrorKind::InvalidNumber
+        );
+        assert_eq!(
+            untrimmed.parse("4 KiB ").unwrap_err().kind(),
+            ParseErrorKind::UnknownUnit
+        );
+        assert_eq!(
+            untrimmed.parse("4 KiB 1 B").unwrap().size,
+            ByteSize::b(4097)
+        );
+        assert_eq!(lenient.parse(" 4 KiB ").unwrap().size, ByteSize::kib(4));
+
+        let jedec = ParseOptions::new().system(UnitSystem::Jedec);
+        assert_eq!(jedec.parse("4 KB").unwrap().size, ByteSize::kib(4));
+        assert_eq!(jedec.parse("4 MB").unwrap().unit, Unit::MiB);
+        assert_eq!(jedec.parse("4 kilobytes").unwrap().size, ByteSize::kb(4));
+        assert_eq!(
+            ParseOptions::new()
+                .system(UnitSystem::Binary)
+                .parse128("1 ZB")
+                .unwrap()
+                .size,
+            ByteSize128::zib(1)
+        );
+    }
+
+    #[test]
+    fn signed() {
+        // shortcut for writing test cases
+        fn parse(s: &str) -> i64 {
+            s.parse::<ByteSizeDelta>().unwrap().0
+        }
+        fn error(s: &str) -> ParseError {
+            s.parse::<ByteSizeDelta>().unwrap_err()
+        }
+
+        assert_eq!(parse("-512 MiB"), -512 * 1024 * 1024);
+        assert_eq!(parse("+1.5 KiB"), 1536);
+        assert_eq!(parse(" -2 KB "), -2000);
+        assert_eq!(parse("1 GiB"), 1 << 30);
+        assert_eq!(parse("-42"), -42);
+        assert_eq!(parse("-8 EiB"), i64::MIN);
+        assert_eq!(parse(&ByteSizeDelta(-348_160).to_string()), -348_160);
+        assert_eq!(parse(&ByteSizeDelta(1 << 20).to_string()), 1 << 20);
+
+        let e = error(" -");
+        assert_eq!((e.kind(), e.span()), (ParseErrorKind::InvalidNumber, 1..2));
+        let e = error(" - 2 KB ");
+        assert_eq!((e.kind(), e.span()), (ParseErrorKind::InvalidNumber, 1..2));
+        assert_eq!(error("+\t1 KiB").kind(), ParseErrorKind::InvalidNumber);
+        let e = error("-1 GiBB");
+        assert_eq!((e.kind(), e.span()), (ParseErrorKind::UnknownUnit, 3..7));
+        assert_eq!(e.suggestion(), Some("GiB"));
+        let e = error("+8 EiB");
+        assert_eq!((e.kind(), e.span()), (ParseErrorKind::Overflow, 1..6));
+        assert_eq!(error("--1 KiB").kind(), ParseErrorKind::InvalidNumber);
+        for input in ["-+1", "+-1", "-+1 KiB", "+ +1"] {
+            let e = error(input);
+            assert_eq!(e.kind(), ParseErrorKind::InvalidNumber, "{}", input);
+        }
+        assert_eq!(error("-+1").span(), 1..2);
+        assert_eq!(error("-9 EiB").kind(), ParseErrorKind::Overflow);
+        assert_eq!(parse("+8.0 EiB"), i64::MAX);
+        assert_eq!(error("+8.00 EiB").kind(), ParseErrorKind::Overflow);
+
+        // formatted deltas parse back, even when rounded up past the largest one
+        for delta in [i64::MAX, i64::MIN, -1, 0, 1 << 62] {
+            assert_eq!(parse(&ByteSizeDelta(delta).to_string()), delta);
+        }
+        assert_eq!(error("").kind(), ParseErrorKind::Empty);
+    }
+
     #[test]
     fn to_and_from_str() {
         // shortcut for writing test cases
-        fn parse(s: &str) -> u64 {
-            s.parse::<ByteSize>().unwrap().0
+        fn parse(s: &str) -> u128 {
+            s.parse::<ByteSize>().unwrap().0.into()
         }
 
-        assert_eq!(parse(&format!("{}", parse("128GB"))), 128 * Unit::GigaByte);
         assert_eq!(
-            parse(&crate::to_string(parse("128.000 GiB"), true)),
-            128 * Unit::GibiByte
+            parse(&format!("{}", parse("128GB"))),
+            128 * Unit::GB.factor()
+        );
+        let max = ByteSize(u64::MAX);
+        assert_eq!(parse(&max.to_string()), u64::MAX.into());
+        assert_eq!(
+            parse(&max.to_string_with(crate::UnitSystem::Decimal)),
+            184 * Unit::EB.factor() / 10
+        );
+
+        for size in [1, 2, 1536, 5 * Unit::GiB.factor()] {
+            let long = ByteSize(size as u64)
+                .display()
+                .style(crate::UnitStyle::Long)
+                .precision(4)
+                .to_string();
+            assert_eq!(parse(&long), size);
+        }
+        assert_eq!(
+            parse(&crate::to_string_with(
+                parse("128.000 GiB") as u64,
+                crate::UnitSystem::Binary
+            )),
+            128 * Unit::GiB.factor()
         );
     }
 }
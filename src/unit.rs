@@ -0,0 +1,405 @@
+use std::borrow::Borrow;
+use std::fmt;
+
+use super::{ByteSize, UnitSystem};
+
+/// Unit prefixes and their spelled-out names in lower case, shared by the runtime and the
+/// const parser.
+pub(crate) const PREFIXES: &[(&str, Unit)] = &[
+    ("", Unit::B),
+    // power of tens
+    ("k", Unit::KB),
+    ("kilo", Unit::KB),
+    ("m", Unit::MB),
+    ("mega", Unit::MB),
+    ("g", Unit::GB),
+    ("giga", Unit::GB),
+    ("t", Unit::TB),
+    ("tera", Unit::TB),
+    ("p", Unit::PB),
+    ("peta", Unit::PB),
+    ("e", Unit::EB),
+    ("exa", Unit::EB),
+    ("z", Unit::ZB),
+    ("zetta", Unit::ZB),
+    ("y", Unit::YB),
+    ("yotta", Unit::YB),
+    ("r", Unit::RB),
+    ("ronna", Unit::RB),
+    ("q", Unit::QB),
+    ("quetta", Unit::QB),
+    // power of twos
+    ("ki", Unit::KiB),
+    ("kibi", Unit::KiB),
+    ("mi", Unit::MiB),
+    ("mebi", Unit::MiB),
+    ("gi", Unit::GiB),
+    ("gibi", Unit::GiB),
+    ("ti", Unit::TiB),
+    ("tebi", Unit::TiB),
+    ("pi", Unit::PiB),
+    ("pebi", Unit::PiB),
+    ("ei", Unit::EiB),
+    ("exbi", Unit::EiB),
+    ("zi", Unit::ZiB),
+    ("zebi", Unit::ZiB),
+    ("yi", Unit::YiB),
+    ("yobi", Unit::YiB),
+    ("ri", Unit::RiB),
+    ("robi", Unit::RiB),
+    ("qi", Unit::QiB),
+    ("quebi", Unit::QiB),
+];
+
+/// Which value of a set decides the shared unit picked by [`Unit::common()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum UnitBasis {
+    /// The largest size, so no value is shown above the unit base.
+    #[default]
+    Max,
+    /// The median size, so most values read naturally.
+    Median,
+}
+
+/// A byte size unit: plain bytes, or a decimal (SI) or binary (IEC) multiple.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Unit {
+    B,
+    // power of tens
+    KB,
+    MB,
+    GB,
+    TB,
+    PB,
+    EB,
+    ZB,
+    YB,
+    RB,
+    QB,
+    // power of twos
+    KiB,
+    MiB,
+    GiB,
+    TiB,
+    PiB,
+    EiB,
+    ZiB,
+    YiB,
+    RiB,
+    QiB,
+}
+
+impl Unit {
+    /// Number of bytes in one of this unit.
+    #[inline(always)]
+    pub const fn factor(self) -> u128 {
+        match self {
+            Unit::B => super::B as u128,
+            // power of tens
+            Unit::KB => super::KB as u128,
+            Unit::MB => super::MB as u128,
+            Unit::GB => super::GB as u128,
+            Unit::TB => super::TB as u128,
+            Unit::PB => super::PB as u128,
+            Unit::EB => super::EB as u128,
+            Unit::ZB => super::ZB,
+            Unit::YB => super::YB,
+            Unit::RB => super::RB,
+            Unit::QB => super::QB,
+            // power of twos
+            Unit::KiB => super::KIB as u128,
+            Unit::MiB => super::MIB as u128,
+            Unit::GiB => super::GIB as u128,
+            Unit::TiB => super::TIB as u128,
+            Unit::PiB => super::PIB as u128,
+            Unit::EiB => super::EIB as u128,
+            Unit::ZiB => super::ZIB,
+            Unit::YiB => super::YIB,
+            Unit::RiB => super::RIB,
+            Unit::QiB => super::QIB,
+        }
+    }
+
+    /// Unit symbol, e.g. `"MiB"`.
+    pub const fn symbol(self) -> &'static str {
+        match self {
+            Unit::B => "B",
+            // power of tens
+            Unit::KB => "KB",
+            Unit::MB => "MB",
+            Unit::GB => "GB",
+            Unit::TB => "TB",
+            Unit::PB => "PB",
+            Unit::EB => "EB",
+            Unit::ZB => "ZB",
+            Unit::YB => "YB",
+            Unit::RB => "RB",
+            Unit::QB => "QB",
+            // power of twos
+            Unit::KiB => "KiB",
+            Unit::MiB => "MiB",
+            Unit::GiB => "GiB",
+            Unit::TiB => "TiB",
+            Unit::PiB => "PiB",
+            Unit::EiB => "EiB",
+            Unit::ZiB => "ZiB",
+            Unit::YiB => "YiB",
+            Unit::RiB => "RiB",
+            Unit::QiB => "QiB",
+        }
+    }
+
+    /// Singular unit name, e.g. `"mebibyte"`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Unit::B => "byte",
+            // power of tens
+            Unit::KB => "kilobyte",
+            Unit::MB => "megabyte",
+            Unit::GB => "gigabyte",
+            Unit::TB => "terabyte",
+            Unit::PB => "petabyte",
+            Unit::EB => "exabyte",
+            Unit::ZB => "zettabyte",
+            Unit::YB => "yottabyte",
+            Unit::RB => "ronnabyte",
+            Unit::QB => "quettabyte",
+            // power of twos
+            Unit::KiB => "kibibyte",
+            Unit::MiB => "mebibyte",
+            Unit::GiB => "gibibyte",
+            Unit::TiB => "tebibyte",
+            Unit::PiB => "pebibyte",
+            Unit::EiB => "exbibyte",
+            Unit::ZiB => "zebibyte",
+            Unit::YiB => "yobibyte",
+            Unit::RiB => "robibyte",
+            Unit::QiB => "quebibyte",
+        }
+    }
+
+    /// The unit [`ByteSize::display()`] picks for `size` in the given system, after rounding
+    /// to its default precision: one byte short of 1 MiB is "1.0 MiB", not "1024.0 KiB". JEDEC
+    /// maps to the binary units, which have the same factors.
+    pub fn best(size: ByteSize, system: UnitSystem) -> Unit {
+        size.display().system(system).picked_unit()
+    }
+
+    /// Picks one unit for a whole set of sizes, e.g. for a table column.
+    ///
+    /// ```
+    /// use bytesize::{ByteSize, Unit, UnitBasis, UnitSystem};
+    ///
+    /// let sizes = [ByteSize::kib(999), ByteSize::mib(1), ByteSize::kib(1536)];
+    /// let unit = Unit::common(&sizes, UnitSystem::Binary, UnitBasis::Max);
+    ///
+    /// let column: Vec<_> = sizes.iter().map(|s| s.display().unit(unit).to_string()).collect();
+    /// assert_eq!(column, ["1.0 MiB", "1.0 MiB", "1.5 MiB"]);
+    /// ```
+    ///
+    /// JEDEC picks the binary units, which a JEDEC [`Display`](crate::Display) still writes
+    /// as `KB`, `MB`, ...:
+    ///
+    /// ```
+    /// # use bytesize::{ByteSize, Unit, UnitBasis, UnitSystem};
+    /// let sizes = [ByteSize::kib(512), ByteSize::kib(1536)];
+    /// let unit = Unit::common(&sizes, UnitSystem::Jedec, UnitBasis::Max);
+    ///
+    /// let column: Vec<_> = sizes
+    ///     .iter()
+    ///     .map(|s| s.display().jedec().unit(unit).to_string())
+    ///     .collect();
+    /// assert_eq!(column, ["0.5 MB", "1.5 MB"]);
+    /// ```
+    pub fn common<I>(sizes: I, system: UnitSystem, basis: UnitBasis) -> Unit
+    where
+        I: IntoIterator,
+        I::Item: Borrow<ByteSize>,
+    {
+        let sizes = sizes.into_iter().map(|size| size.borrow().0);
+        let bytes = match basis {
+            UnitBasis::Max => sizes.max().unwrap_or(0),
+            UnitBasis::Median => {
+                let mut sizes: Vec<u64> = sizes.collect();
+                sizes.sort_unstable();
+                match sizes.len() {
+                    0 => 0,
+                    n => sizes[(n - 1) / 2],
+                }
+            }
+        };
+        Unit::best(ByteSize(bytes), system)
+    }
+
+    pub(crate) const fn from_exponent(system: UnitSystem, exp: usize) -> Unit {
+        match (system, exp) {
+            (_, 0) => Unit::B,
+            // power of tens
+            (UnitSystem::Decimal, 1) => Unit::KB,
+            (UnitSystem::Decimal, 2) => Unit::MB,
+            (UnitSystem::Decimal, 3) => Unit::GB,
+            (UnitSystem::Decimal, 4) => Unit::TB,
+            (UnitSystem::Decimal, 5) => Unit::PB,
+            (UnitSystem::Decimal, 6) => Unit::EB,
+            (UnitSystem::Decimal, 7) => Unit::ZB,
+            (UnitSystem::Decimal, 8) => Unit::YB,
+            (UnitSystem::Decimal, 9) => Unit::RB,
+            (UnitSystem::Decimal, _) => Unit::QB,
+            // power of twos
+            (_, 1) => Unit::KiB,
+            (_, 2) => Unit::MiB,
+            (_, 3) => Unit::GiB,
+            (_, 4) => Unit::TiB,
+            (_, 5) => Unit::PiB,
+            (_, 6) => Unit::EiB,
+            (_, 7) => Unit::ZiB,
+            (_, 8) => Unit::YiB,
+            (_, 9) => Unit::RiB,
+            (_, _) => Unit::QiB,
+        }
+    }
+
+    /// Unit system this unit belongs to; plain bytes count as binary.
+    pub(crate) const fn system(self) -> UnitSystem {
+        match self {
+            Unit::KB
+            | Unit::MB
+            | Unit::GB
+            | Unit::TB
+            | Unit::PB
+            | Unit::EB
+            | Unit::ZB
+            | Unit::YB
+            | Unit::RB
+            | Unit::QB => UnitSystem::Decimal,
+            _ => UnitSystem::Binary,
+        }
+    }
+
+    /// Power of the system base this unit stands for.
+    pub(crate) const fn exponent(self) -> usize {
+        match self {
+            Unit::B => 0,
+            Unit::KB | Unit::KiB => 1,
+            Unit::MB | Unit::MiB => 2,
+            Unit::GB | Unit::GiB => 3,
+            Unit::TB | Unit::TiB => 4,
+            Unit::PB | Unit::PiB => 5,
+            Unit::EB | Unit::EiB => 6,
+            Unit::ZB | Unit::ZiB => 7,
+            Unit::YB | Unit::YiB => 8,
+            Unit::RB | Unit::RiB => 9,
+            Unit::QB | Unit::QiB => 10,
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.symbol())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn best() {
+        assert_eq!(Unit::B, Unit::best(ByteSize::b(1023), UnitSystem::Binary));
+        assert_eq!(Unit::KB, Unit::best(ByteSize::b(1023), UnitSystem::Decimal));
+        assert_eq!(Unit::MiB, Unit::best(ByteSize::mib(5), UnitSystem::Jedec));
+        assert_eq!(Unit::TB, Unit::best(ByteSize::tib(5), UnitSystem::Decimal));
+        assert_eq!(
+            Unit::EiB,
+            Unit::best(ByteSize(u64::MAX), UnitSystem::Binary)
+        );
+        assert_eq!(
+            Unit::EB,
+            Unit::best(ByteSize(u64::MAX), UnitSystem::Decimal)
+        );
+
+        // rounding promotes a size just below a unit to that unit, as when displayed
+        let size = ByteSize::mib(1) - ByteSize::b(1);
+        assert_eq!(Unit::MiB, Unit::best(size, UnitSystem::Binary));
+        assert_eq!(Unit::MiB, Unit::best(size, UnitSystem::Jedec));
+        assert_eq!(
+            Unit::KiB,
+            Unit::best(ByteSize::kib(1023), UnitSystem::Binary)
+        );
+        assert_eq!(
+            Unit::KB,
+            Unit::best(ByteSize::b(999_949), UnitSystem::Decimal)
+        );
+        assert_eq!(
+            Unit::MB,
+            Unit::best(ByteSize::b(999_950), UnitSystem::Decimal)
+        );
+    }
+
+    #[test]
+    fn common() {
+        let sizes = vec![
+            ByteSize::kib(2),
+            ByteSize::kib(999),
+            ByteSize::mib(1),
+            ByteSize::kib(3),
+            ByteSize::gib(4),
+        ];
+
+        assert_eq!(
+            Unit::GiB,
+            Unit::common(&sizes, UnitSystem::Binary, UnitBasis::Max)
+        );
+        assert_eq!(
+            Unit::KiB,
+            Unit::common(&sizes, UnitSystem::Binary, UnitBasis::Median)
+        );
+        assert_eq!(
+            Unit::MB,
+            Unit::common(sizes.iter().take(3), UnitSystem::Decimal, UnitBasis::Max)
+        );
+        let jedec = Unit::common(&sizes, UnitSystem::Jedec, UnitBasis::Median);
+        assert_eq!(Unit::KiB, jedec);
+        assert_eq!(
+            "999.0 KB",
+            sizes[1].display().jedec().unit(jedec).to_string()
+        );
+        let sizes = [ByteSize::kib(512), ByteSize::mib(1) - ByteSize::b(1)];
+        let unit = Unit::common(sizes, UnitSystem::Binary, UnitBasis::Max);
+        assert_eq!(Unit::MiB, unit);
+        assert_eq!("1.0 MiB", sizes[1].display().unit(unit).to_string());
+        assert_eq!(
+            Unit::B,
+            Unit::common(
+                Vec::<ByteSize>::new(),
+                UnitSystem::Binary,
+                UnitBasis::Median
+            )
+        );
+    }
+
+    #[test]
+    fn factor_matches_symbol() {
+        for unit in [
+            Unit::B,
+            Unit::KB,
+            Unit::MiB,
+            Unit::GB,
+            Unit::PiB,
+            Unit::EB,
+            Unit::EiB,
+            Unit::ZB,
+            Unit::YiB,
+            Unit::RB,
+            Unit::QiB,
+        ] {
+            assert_eq!(unit, unit.symbol().parse::<Unit>().unwrap());
+            assert_eq!(unit, unit.name().parse::<Unit>().unwrap());
+            assert_eq!(
+                unit.factor(),
+                u128::from(unit.system().base()).pow(unit.exponent() as u32)
+            );
+        }
+    }
+}